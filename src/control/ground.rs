@@ -1,5 +1,5 @@
 use crate::{
-    GRAVITY, PIXELS_PER_METER,
+    WorldScale,
     control::{Jump, Movement},
     prelude::*,
 };
@@ -141,18 +141,18 @@ fn update_ground_contacts(
 #[derive(Component, Debug, Clone, Copy)]
 #[require(GroundMoveState)]
 pub struct GroundMove {
-    /// Grounded walking speed.
+    /// Grounded walking speed, in meters per second.
     pub move_speed: f32,
-    /// Horizontal force for walking while grounded.
+    /// Horizontal force for walking while grounded, in meters per second squared.
     pub grounded_move_accel: f32,
-    /// Horizontal force for changing directions while mid-air.
+    /// Horizontal force for changing directions while mid-air, in meters per second squared.
     pub aired_move_accel: f32,
 }
 
 impl Default for GroundMove {
     fn default() -> Self {
         // Reach 5 m/s...
-        let speed = 5. * PIXELS_PER_METER;
+        let speed = 5.;
         Self {
             // ...in 1/20th of a second.
             move_speed: speed,
@@ -195,7 +195,7 @@ fn ground_move(actions: Query<(&Action<Movement>, &ActionEvents, &ActionOf<Groun
 #[derive(Component, Debug, Clone, Copy)]
 #[require(GroundJumpState)]
 pub struct GroundJump {
-    /// Maximum jump height if the jump action is not interrupted.
+    /// Maximum jump height in meters if the jump action is not interrupted.
     pub jump_height: f32,
     /// Grace time for jumping when attempted to do so before grounded.
     pub buffer_time: Duration,
@@ -207,7 +207,7 @@ impl Default for GroundJump {
     fn default() -> Self {
         Self {
             // Jump as high as 2.5 meters.
-            jump_height: 2.5 * PIXELS_PER_METER,
+            jump_height: 2.5,
             buffer_time: Duration::from_millis(100),
             coyote_time: Duration::from_millis(100),
         }
@@ -244,6 +244,7 @@ fn ground_jump(
 
 fn evaluate_ground(
     time: Res<Time>,
+    scale: Res<WorldScale>,
     states: Query<(
         &mut GroundControlState,
         &mut GroundControlStatePrevious,
@@ -256,6 +257,8 @@ fn evaluate_ground(
 ) {
     let now = time.elapsed();
     let dt = time.delta_secs();
+    let gravity = scale.gravity();
+    let pixels_per_meter = scale.pixels_per_meter;
     states.par_iter_inner().for_each(
         |(mut control_state, mut control_state_previous, mut control_direction, mut contacts, movement, jump, mut forces)| {
             let mut next_state = *control_state;
@@ -268,7 +271,7 @@ fn evaluate_ground(
                 // `dv_cap`   : Change in velocity the actor can actually make in this frame.
                 // `dv_factor`: Multiplier to the acceleration to not overaccelerate.
                 let grounded = contacts.is_grounded(now, Duration::ZERO);
-                let rel_move_vel = state.as_vec2().x.clamp(-1., 1.) * param.move_speed;
+                let rel_move_vel = state.as_vec2().x.clamp(-1., 1.) * param.move_speed * pixels_per_meter;
 
                 let vel0_x = forces.linear_velocity().x;
                 let vel1_x = grounded.unwrap_or(Vec2::ZERO).x + rel_move_vel;
@@ -285,11 +288,11 @@ fn evaluate_ground(
                             },
                         };
 
-                        param.grounded_move_accel
+                        param.grounded_move_accel * pixels_per_meter
                     }
                     None => {
                         next_state = GroundControlState::Hover { steering: state.is_moving() };
-                        param.aired_move_accel
+                        param.aired_move_accel * pixels_per_meter
                     }
                 } * if (dv_x_target > 0. && cling_right) || (dv_x_target < 0. && cling_left) { 0. } else { 1. };
 
@@ -308,6 +311,7 @@ fn evaluate_ground(
             }
 
             if let Some((&param, mut state)) = jump {
+                let jump_height = param.jump_height * pixels_per_meter;
                 // Apply an upwards velocity of sqrt(2gh), as stated in high school physics class.
                 // If the actor stops jumping before reaching maximum height, cancel the impulse.
                 match (state.tried, state.acted) {
@@ -320,7 +324,7 @@ fn evaluate_ground(
                             state.time = Some(0.);
                             next_state = GroundControlState::Jump;
 
-                            forces.linear_velocity_mut().y = ground_velocity.y + (2. * param.jump_height * GRAVITY).sqrt();
+                            forces.linear_velocity_mut().y = ground_velocity.y + (2. * jump_height * gravity).sqrt();
                         }
                     }
                     (.., true) => {
@@ -335,9 +339,9 @@ fn evaluate_ground(
                     }
                     (None, false) => {
                         if let Some(commited) = state.time.take() {
-                            let total = (2. * param.jump_height * GRAVITY).sqrt();
-                            if commited < total / GRAVITY {
-                                let leftover = total - GRAVITY * commited;
+                            let total = (2. * jump_height * gravity).sqrt();
+                            if commited < total / gravity {
+                                let leftover = total - gravity * commited;
                                 forces.linear_velocity_mut().y -= leftover;
                             }
                         }
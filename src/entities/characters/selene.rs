@@ -72,10 +72,10 @@ fn spawn_selene(mut commands: Commands, mut messages: MessageReader<EntityCreate
                 actions!(GroundControl[(
                     Action::<Movement>::new(),
                     Down::new(0.5),
-                    Bindings::spawn(Cardinal::arrows()),
+                    Bindings::spawn((Cardinal::arrows(), Cardinal::dpad(), Axial::left_stick())),
                 ), (
                     Action::<Jump>::new(),
-                    bindings![KeyCode::KeyZ],
+                    bindings![KeyCode::KeyZ, GamepadButton::South],
                 )]),
             ),
         ));
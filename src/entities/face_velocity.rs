@@ -0,0 +1,66 @@
+use crate::prelude::*;
+
+/// Rotates a rigid body so its local `+X` axis points along its [`LinearVelocity`].
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Debug, Default, FromWorld, Clone)]
+pub struct FaceVelocity {
+    /// Exponential turning rate, per second. Non-positive values snap immediately.
+    pub lerp: f32,
+    /// Speeds below this keep the current rotation, so near-still bodies don't spin erratically.
+    pub min_speed: f32,
+}
+
+impl Default for FaceVelocity {
+    fn default() -> Self {
+        Self { lerp: 0., min_speed: 1e-3 }
+    }
+}
+
+fn face_velocity(time: Res<Time>, bodies: Query<(&FaceVelocity, &LinearVelocity, &mut Rotation)>) {
+    let dt = time.delta_secs();
+    bodies.par_iter_inner().for_each(|(&face, &vel, mut rot)| {
+        if vel.length_squared() < face.min_speed * face.min_speed {
+            return
+        }
+
+        let target = Rotation::radians(vel.to_angle());
+        let new_rot = match face.lerp > 0. {
+            true => rot.slerp(target, 1. - (-face.lerp * dt).exp()),
+            false => target,
+        };
+
+        rot.set_if_neq(new_rot);
+    });
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(FixedUpdate, face_velocity);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn face(vel: Vec2, rot: Rotation) -> f32 {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        let entity = world.spawn((FaceVelocity::default(), LinearVelocity(vel), rot)).id();
+
+        world.run_system_once(face_velocity).unwrap();
+        world.get::<Rotation>(entity).unwrap().as_radians()
+    }
+
+    #[test]
+    fn faces_velocity() {
+        assert!(face(vec2(5., 0.), Rotation::degrees(90.)).abs() < 1e-5);
+        assert!((face(vec2(0., 5.), Rotation::IDENTITY) - FRAC_PI_2).abs() < 1e-5);
+
+        // Too slow to turn.
+        assert!((face(vec2(1e-4, 0.), Rotation::degrees(90.)) - FRAC_PI_2).abs() < 1e-5);
+        assert!(face(Vec2::ZERO, Rotation::IDENTITY).abs() < 1e-5);
+    }
+}
@@ -5,7 +5,7 @@ use crate::{math::Transform2d, prelude::*};
 /// Uses PBD (position-based dynamics) instead of the usual Semi-Implicit Euler Integration here as
 /// the hair deals with constraints, and PBD proves the correct tool to give a nice stable result.
 #[derive(Component, Debug)]
-#[component(on_insert = Self::on_insert)]
+#[component(on_insert = Self::on_insert, on_despawn = Self::on_despawn)]
 #[require(Transform)]
 pub struct Hair {
     segments: Vec<HairSegment>,
@@ -56,6 +56,16 @@ impl Hair {
             seg.entity = commands.spawn((trns, Transform2d::from(trns), TransformInterpolation)).id();
         }
     }
+
+    /// Strands aren't children of the hair, so they have to be despawned manually.
+    fn on_despawn(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
+        let (entities, mut commands) = world.entities_and_commands();
+        for strand in entities.get(entity).unwrap().get::<Self>().unwrap().iter_strands() {
+            if strand != Entity::PLACEHOLDER {
+                commands.entity(strand).try_despawn();
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
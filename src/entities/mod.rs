@@ -1,10 +1,14 @@
+mod face_velocity;
 mod hair;
+mod oscillate;
+pub use face_velocity::*;
 pub use hair::*;
+pub use oscillate::*;
 
 pub mod characters;
 
 use crate::prelude::*;
 
 pub fn plugin(app: &mut App) {
-    app.add_plugins((characters::plugin, hair::plugin));
+    app.add_plugins((characters::plugin, face_velocity::plugin, hair::plugin, oscillate::plugin));
 }
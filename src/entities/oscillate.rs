@@ -0,0 +1,121 @@
+use crate::{math::Transform2d, prelude::*};
+
+/// Sinusoidally offsets a [`Transform2d`] over game time, e.g. for idle bobbing or pulsing. The
+/// offset is applied on top of whatever else moves the transform, and only depends on the elapsed
+/// game time and `phase`, so it's deterministic across runs.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[require(Transform2d, OscillateOffset)]
+#[reflect(Component, Debug, Default, FromWorld, Clone)]
+pub struct Oscillate {
+    pub target: OscillateTarget,
+    /// Direction the translation or scale is offset along. Unused for rotations.
+    pub axis: Vec2,
+    /// Peak offset; in radians for rotations.
+    pub amplitude: f32,
+    /// Cycles per second.
+    pub frequency: f32,
+    /// Offset into the cycle, where `1` is a full cycle.
+    pub phase: f32,
+}
+
+impl Default for Oscillate {
+    fn default() -> Self {
+        Self {
+            target: default(),
+            axis: Vec2::Y,
+            amplitude: 1.,
+            frequency: 1.,
+            phase: 0.,
+        }
+    }
+}
+
+impl Oscillate {
+    /// Returns the signed offset magnitude at `elapsed` game time.
+    pub fn sample(self, elapsed: Duration) -> f32 {
+        // Wrap in `f64` first, so long play sessions don't lose precision.
+        let cycle = (elapsed.as_secs_f64() * self.frequency as f64 + self.phase as f64).rem_euclid(1.) as f32;
+        self.amplitude * (cycle * TAU).sin()
+    }
+}
+
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Debug, Default, Clone, PartialEq)]
+pub enum OscillateTarget {
+    #[default]
+    Translation,
+    Scale,
+    Rotation,
+}
+
+/// The last sampled offset, so it can be replaced instead of accumulated.
+#[derive(Component, Debug, Default, Clone, Copy)]
+struct OscillateOffset(f32);
+
+fn oscillate(time: Res<Time>, oscillates: Query<(&Oscillate, &mut OscillateOffset, &mut Transform2d)>) {
+    let elapsed = time.elapsed();
+    oscillates.par_iter_inner().for_each(|(&oscillate, mut offset, mut trns)| {
+        let sample = oscillate.sample(elapsed);
+        let delta = sample - mem::replace(&mut offset.0, sample);
+
+        match oscillate.target {
+            OscillateTarget::Translation => trns.translation += (oscillate.axis * delta).extend(0.),
+            OscillateTarget::Scale => trns.scale += oscillate.axis * delta,
+            OscillateTarget::Rotation => trns.rotation = trns.rotation * Rot2::radians(delta),
+        }
+    });
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(Update, oscillate);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn secs(secs: f32) -> Duration {
+        Duration::from_secs_f32(secs)
+    }
+
+    #[test]
+    fn sample() {
+        let oscillate = Oscillate {
+            amplitude: 2.,
+            frequency: 2.,
+            ..default()
+        };
+
+        for (time, expected) in [(0., 0.), (0.125, 2.), (0.25, 0.), (0.375, -2.), (0.625, 2.), (1000.125, 2.)] {
+            assert!((oscillate.sample(secs(time)) - expected).abs() < 1e-4, "sample at {time}s");
+        }
+
+        let shifted = Oscillate { phase: 0.25, ..oscillate };
+        assert!((shifted.sample(Duration::ZERO) - 2.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn offset_does_not_accumulate() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+
+        let entity = world
+            .spawn((Transform2d::from_xy(10., 20.), Oscillate {
+                axis: Vec2::X,
+                amplitude: 3.,
+                ..default()
+            }))
+            .id();
+
+        for (dt, expected) in [(0.25, 13.), (0.5, 7.), (0.25, 10.), (0.25, 13.)] {
+            world.resource_mut::<Time>().advance_by(secs(dt));
+            world.run_system_once(oscillate).unwrap();
+
+            let trns = world.get::<Transform2d>(entity).unwrap();
+            assert!((trns.translation.x - expected).abs() < 1e-4);
+            assert_eq!(trns.translation.y, 20.);
+        }
+    }
+}
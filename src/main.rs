@@ -47,7 +47,7 @@ pub mod prelude {
         camera::{
             ImageRenderTarget, RenderTarget,
             primitives::Aabb,
-            visibility::{RenderLayers, VisibilityClass, add_visibility_class},
+            visibility::{RenderLayers, VisibilityClass, VisibilitySystems, add_visibility_class},
         },
         core_pipeline::{
             core_2d::{CORE_2D_DEPTH_FORMAT, Transparent2d},
@@ -126,10 +126,14 @@ pub mod prelude {
 
 use prelude::*;
 
-#[cfg(not(target_family = "wasm"))]
+#[cfg(all(not(feature = "dev"), not(target_family = "wasm")))]
 #[global_allocator]
 static ALLOC: mimalloc_redirect::MiMalloc = mimalloc_redirect::MiMalloc;
 
+#[cfg(all(feature = "dev", not(target_family = "wasm")))]
+#[global_allocator]
+static ALLOC: util::CountingAlloc<mimalloc_redirect::MiMalloc> = util::CountingAlloc::new(mimalloc_redirect::MiMalloc);
+
 #[cfg(not(target_family = "wasm"))]
 fn print_mimalloc_version(_: &mut App) {
     info!("Using MiMalloc {}", mimalloc_redirect::MiMalloc::get_version());
@@ -138,6 +142,52 @@ fn print_mimalloc_version(_: &mut App) {
 pub const PIXELS_PER_METER: f32 = 16.;
 pub const GRAVITY: f32 = 16. * PIXELS_PER_METER;
 
+/// Runtime world scaling, defaulting to [`PIXELS_PER_METER`] and [`GRAVITY`].
+///
+/// Avian's length unit is configured from [`PIXELS_PER_METER`] once when building the app, so
+/// changing `pixels_per_meter` here doesn't affect physics tolerances; it only affects gameplay
+/// code that reads this resource. [`Gravity`] is kept in sync with [`WorldScale::gravity`] whenever
+/// this resource changes. Invalid (non-positive or non-finite) values are rejected and reverted to
+/// the previous ones.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq)]
+#[reflect(Resource, Debug, Default, FromWorld, Clone, PartialEq)]
+pub struct WorldScale {
+    pub pixels_per_meter: f32,
+    /// Downwards gravitational acceleration, in meters per second squared.
+    pub gravity_meters: f32,
+}
+
+impl WorldScale {
+    /// Downwards gravitational acceleration, in pixels per second squared.
+    pub fn gravity(self) -> f32 {
+        self.gravity_meters * self.pixels_per_meter
+    }
+
+    pub fn is_valid(self) -> bool {
+        let valid = |value: f32| value.is_finite() && value > 0.;
+        valid(self.pixels_per_meter) && valid(self.gravity_meters)
+    }
+}
+
+impl Default for WorldScale {
+    fn default() -> Self {
+        Self {
+            pixels_per_meter: PIXELS_PER_METER,
+            gravity_meters: GRAVITY / PIXELS_PER_METER,
+        }
+    }
+}
+
+fn apply_world_scale(mut scale: ResMut<WorldScale>, mut last_valid: Local<WorldScale>, mut gravity: ResMut<Gravity>) {
+    if scale.is_valid() {
+        *last_valid = *scale;
+        gravity.0 = Vec2::NEG_Y * scale.gravity();
+    } else {
+        warn!("Invalid world scale {:?}; reverting to {:?}", *scale, *last_valid);
+        *scale.bypass_change_detection() = *last_valid;
+    }
+}
+
 #[derive(Reflect, States, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 #[reflect(State, Debug, Default, FromWorld, Clone, PartialEq, Hash)]
 pub enum GameState {
@@ -179,7 +229,7 @@ pub fn main() -> AppExit {
             EnhancedInputPlugin,
             FramepacePlugin,
         ))
-        .insert_resource(Gravity(Vec2::NEG_Y * GRAVITY))
+        .init_resource::<WorldScale>()
         .insert_resource(DefaultFriction(Friction::new(0.)))
         .init_state::<GameState>()
         .add_plugins((
@@ -194,6 +244,7 @@ pub fn main() -> AppExit {
             util::plugin,
             world::plugin,
         ))
+        .add_systems(PreUpdate, apply_world_scale.run_if(resource_changed::<WorldScale>))
         .add_systems(OnExit(GameState::AssetLoading), |mut load_level: ResMut<world::LoadLevel>| {
             load_level.load("eastern_beacon");
         })
@@ -231,13 +282,19 @@ fn set_panic_hook() {
         //      malicious backtrace to execute arbitrary commands on the host.
         //      Someday, I would like to sanitize the inputs.
         error!("{backtrace}");
-        tfd::MessageBox::new(
-            "Crash!",
-            &format!(
-                "An unrecoverable error has occured in Centripetal. A crash log has been written at {} which contains the error message and backtrace below.\nPlease report this to https://github.com/GglLfr/centripetal\n\n{backtrace}",
-                log_file.display(),
-            ),
-        ).with_icon(tfd::MessageBoxIcon::Error).run_modal();
+        let headless = is_headless();
+        if headless {
+            // The logger might not be set up yet, so print to `stderr` directly for CI logs.
+            eprintln!("Crash! A crash log will be written at {}\n\n{backtrace}", log_file.display());
+        } else {
+            tfd::MessageBox::new(
+                "Crash!",
+                &format!(
+                    "An unrecoverable error has occured in Centripetal. A crash log has been written at {} which contains the error message and backtrace below.\nPlease report this to https://github.com/GglLfr/centripetal\n\n{backtrace}",
+                    log_file.display(),
+                ),
+            ).with_icon(tfd::MessageBoxIcon::Error).run_modal();
+        }
 
         #[cfg(not(feature = "dev"))]
         if let Err(e) = fs::File::create(log_file).and_then(|mut file| {
@@ -246,12 +303,32 @@ fn set_panic_hook() {
             file.write_all(backtrace.as_bytes())?;
             file.sync_all()
         }) {
-            tfd::MessageBox::new(
-                "Worse than crash!",
-                &format!("Couldn't write crash log file: {e}\n\nSure hope you can copy the crashlog text in some other way..."),
-            )
-            .with_icon(tfd::MessageBoxIcon::Error)
-            .run_modal();
+            let message = format!("Couldn't write crash log file: {e}\n\nSure hope you can copy the crashlog text in some other way...");
+            if headless {
+                eprintln!("Worse than crash! {message}");
+            } else {
+                tfd::MessageBox::new("Worse than crash!", &message)
+                    .with_icon(tfd::MessageBoxIcon::Error)
+                    .run_modal();
+            }
         }
     }));
 }
+
+/// Whether there's nobody to click away a modal message box, e.g. in CI runs. Set
+/// `CENTRIPETAL_HEADLESS` to `1` or `0` to override the detection.
+fn is_headless() -> bool {
+    use std::env::var_os;
+
+    if let Some(value) = var_os("CENTRIPETAL_HEADLESS") {
+        return !value.is_empty() && value != "0"
+    }
+
+    if var_os("CI").is_some() {
+        return true
+    }
+
+    // Windows and macOS always have a desktop to show message boxes on; other Unix-likes need an
+    // X11 or Wayland session.
+    cfg!(all(unix, not(target_os = "macos"))) && var_os("DISPLAY").is_none() && var_os("WAYLAND_DISPLAY").is_none()
+}
@@ -107,6 +107,22 @@ impl Transform2d {
     pub fn affine_and_z(self) -> (Affine2, f32) {
         (self.affine(), self.translation.z)
     }
+
+    /// Linearly interpolates translation and scale, and spherically interpolates rotation along the
+    /// shortest arc. Returns exactly `self` at `t = 0` and `rhs` at `t = 1`.
+    pub fn lerp(self, rhs: Self, t: f32) -> Self {
+        if t == 0. {
+            self
+        } else if t == 1. {
+            rhs
+        } else {
+            Self {
+                translation: self.translation.lerp(rhs.translation, t),
+                rotation: self.rotation.slerp(rhs.rotation, t),
+                scale: self.scale.lerp(rhs.scale, t),
+            }
+        }
+    }
 }
 
 #[derive(Reflect, Component, Default, Debug, Clone, Copy, PartialEq, Deref, DerefMut)]
@@ -210,3 +226,42 @@ pub(super) fn plugin(app: &mut App) {
             .in_set(TransformSystems::Propagate),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn lerp_endpoints() {
+        let a = Transform2d {
+            translation: vec3(0.1, -3.7, 0.3),
+            rotation: Rot2::degrees(33.3),
+            scale: vec2(0.7, 1.9),
+        };
+        let b = Transform2d {
+            translation: vec3(12.9, 4.1, -0.7),
+            rotation: Rot2::degrees(-121.7),
+            scale: vec2(2.3, 0.1),
+        };
+
+        assert_eq!(a.lerp(b, 0.), a);
+        assert_eq!(a.lerp(b, 1.), b);
+    }
+
+    #[test]
+    fn lerp_rotates_the_short_way_round() {
+        let a = Transform2d::IDENTITY.with_rotation(Rot2::degrees(170.));
+        let b = Transform2d::IDENTITY.with_rotation(Rot2::degrees(-170.));
+
+        // Through ±180°, not back through 0°.
+        let mid = a.lerp(b, 0.5).rotation.as_radians();
+        assert!((mid.abs() - PI).abs() < 1e-5, "{mid}");
+
+        let quarter = a.lerp(b, 0.25).rotation.as_degrees();
+        assert!((quarter - 175.).abs() < 1e-3, "{quarter}");
+        let three_quarters = a.lerp(b, 0.75).rotation.as_degrees();
+        assert!((three_quarters + 175.).abs() < 1e-3, "{three_quarters}");
+    }
+}
@@ -201,6 +201,31 @@ impl<T: FreelyMutableState> Default for ProgressTransitions<T> {
     }
 }
 
+/// Reports the progress of whatever loading is happening in the current state of `T`, so a single
+/// loading screen can serve every state registered with [`ProgressPlugin::trans()`].
+#[derive(SystemParam)]
+pub struct LoadingProgress<'w, T: FreelyMutableState> {
+    tracker: Res<'w, ProgressTracker<T>>,
+    transitions: Res<'w, ProgressTransitions<T>>,
+    state: Res<'w, State<T>>,
+}
+
+impl<T: FreelyMutableState> LoadingProgress<'_, T> {
+    /// Returns `true` if the current state transitions to another once its progress finishes.
+    pub fn is_loading(&self) -> bool {
+        self.transitions.0.contains_key(&**self.state)
+    }
+
+    /// Returns the normalized progress in `0..=1`, or `None` if the current state isn't a loading
+    /// state or nothing has reported progress yet.
+    pub fn get(&self) -> Option<f32> {
+        self.is_loading()
+            .then(|| self.tracker.count_progress_f32())
+            .flatten()
+            .map(|progress| progress.clamp(0., 1.))
+    }
+}
+
 #[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProgressSystems {
     UpdateTransitions,
@@ -253,3 +278,48 @@ impl<T: FreelyMutableState> Plugin for ProgressPlugin<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::GameState;
+
+    #[test]
+    fn loading_progress() {
+        let mut world = World::new();
+        world.insert_resource(ProgressTracker::<GameState>::default());
+        world.insert_resource(ProgressTransitions(
+            [(GameState::LevelLoading, GameState::InGame { paused: false })].into(),
+        ));
+        world.insert_resource(State::new(GameState::LevelLoading));
+
+        let mut tracker = world.resource_mut::<ProgressTracker<GameState>>();
+        let [a, b] = [tracker.register(), tracker.register()];
+
+        let get = |world: &mut World| {
+            world
+                .run_system_once(|progress: LoadingProgress<GameState>| (progress.is_loading(), progress.get()))
+                .unwrap()
+        };
+
+        // Nothing reported yet.
+        assert_eq!(get(&mut world), (true, None));
+
+        let tracker = world.resource::<ProgressTracker<GameState>>();
+        tracker.update(a, (1, 4));
+        tracker.update(b, (1, 2));
+        let (loading, progress) = get(&mut world);
+        assert!(loading);
+        assert!((progress.unwrap() - 2. / 6.).abs() < 1e-6);
+
+        // Overshooting progress is clamped.
+        world.resource::<ProgressTracker<GameState>>().update(a, (9, 4));
+        assert_eq!(get(&mut world), (true, Some(1.)));
+
+        // States without a transition aren't loading, regardless of the tracker.
+        world.insert_resource(State::new(GameState::Menu));
+        assert_eq!(get(&mut world), (false, None));
+    }
+}
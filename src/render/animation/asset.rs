@@ -9,7 +9,9 @@ pub struct AnimationSheet {
     pub region: Handle<AtlasRegion>,
     pub frames: Vec<AnimationFrame>,
     pub frame_tags: HashMap<String, AnimationIndices>,
-    pub event_tags: HashMap<String, AnimationIndices>,
+    /// Sorted by name, which is the order [`AnimateFrame`](crate::render::animation::AnimateFrame)
+    /// events of tags covering the same frame are triggered in.
+    pub event_tags: BTreeMap<String, AnimationIndices>,
 }
 
 #[derive(Reflect, Debug)]
@@ -26,11 +28,35 @@ pub struct AnimationIndices {
     pub direction: AnimationDirection,
 }
 
-#[derive(Reflect, Debug, Deserialize)]
+impl AnimationIndices {
+    /// Returns the first and last frame index of a forward pass, and the index increment to step
+    /// from the former towards the latter.
+    pub fn pass(&self) -> (usize, usize, isize) {
+        let (start, end) = (*self.indices.start(), *self.indices.end());
+        match self.direction {
+            AnimationDirection::Forward | AnimationDirection::PingPong => (start, end, 1),
+            AnimationDirection::Reverse | AnimationDirection::PingPongReverse => (end, start, -1),
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum AnimationDirection {
     Forward,
     Reverse,
+    /// Forward, then backward, without repeating the frames at either end.
+    #[serde(rename = "pingpong")]
+    PingPong,
+    /// Backward, then forward, without repeating the frames at either end.
+    #[serde(rename = "pingpong_reverse")]
+    PingPongReverse,
+}
+
+impl AnimationDirection {
+    pub fn is_ping_pong(self) -> bool {
+        matches!(self, Self::PingPong | Self::PingPongReverse)
+    }
 }
 
 pub struct AnimationSheetLoader;
@@ -170,7 +196,7 @@ impl AssetLoader for AnimationSheetLoader {
             })?,
             region: load_context.add_loaded_labeled_asset("region", region),
             frame_tags,
-            event_tags,
+            event_tags: event_tags.into_iter().collect(),
         })
     }
 }
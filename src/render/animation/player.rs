@@ -2,7 +2,7 @@ use crate::{
     math::{GlobalTransform2d, Transform2d},
     prelude::*,
     render::{
-        animation::{AnimationDirection, AnimationFrame, AnimationIndices, AnimationSheet},
+        animation::{AnimationFrame, AnimationIndices, AnimationSheet},
         painter::{Painter, PainterParam},
     },
 };
@@ -98,11 +98,39 @@ impl Default for AnimationEvents {
 #[reflect(Component, Debug, Default, FromWorld, Clone)]
 pub struct AnimationEventsEnabled;
 
+/// Playback speed multiplier of an [`Animation`]. Entities without this component play at normal
+/// speed. Values are clamped to `0..=MAX`, with non-finite values treated as `0`.
+#[derive(Reflect, Component, Debug, Clone, Copy, PartialEq)]
+#[reflect(Component, Debug, Default, FromWorld, Clone, PartialEq)]
+pub struct AnimationSpeed(pub f32);
+
+impl AnimationSpeed {
+    pub const MAX: f32 = 1000.;
+
+    pub fn get(self) -> f32 {
+        match self.0.is_finite() {
+            true => self.0.clamp(0., Self::MAX),
+            false => 0.,
+        }
+    }
+}
+
+impl Default for AnimationSpeed {
+    fn default() -> Self {
+        Self(1.)
+    }
+}
+
 #[derive(Component, Debug, Default, Clone, Copy)]
 struct AnimationState {
     index: usize,
     time: Duration,
     ticked: bool,
+    /// `true` if in the second half of a ping-pong animation.
+    backward: bool,
+    /// `true` if the tag was just (re)started and its first frame hasn't triggered [`AnimateFrame`]
+    /// yet.
+    entering: bool,
 }
 
 #[derive(QueryData)]
@@ -159,6 +187,18 @@ pub struct AnimateLoop {
     pub tag: Cow<'static, str>,
 }
 
+/// Triggered when an [`Animation`] enters the frame range of one of its sheet's
+/// [event tags](AnimationSheet::event_tags), including on the first frame of a newly set tag. If a
+/// tick skips over several frames, an event is triggered for each of them in playback order; tags
+/// covering the same frame trigger in name order.
+#[derive(EntityEvent, Debug, Clone)]
+pub struct AnimateFrame {
+    pub entity: Entity,
+    pub tag: Cow<'static, str>,
+    pub event: String,
+    pub frame: usize,
+}
+
 fn on_tag_inserted(
     insert: On<Insert, AnimationTag>,
     mut commands: Commands,
@@ -169,10 +209,7 @@ fn on_tag_inserted(
 
     let Some(sheet) = sheets.get(anim_query.animation.id()) else { return };
     let Some(frame_tag) = sheet.frame_tags.get(anim_query.tag.as_str()) else { return };
-    let first = match frame_tag.direction {
-        AnimationDirection::Forward => *frame_tag.indices.start(),
-        AnimationDirection::Reverse => *frame_tag.indices.end(),
-    };
+    let (first, ..) = frame_tag.pass();
 
     if transition.is_some() {
         commands.entity(insert.entity).try_remove::<AnimationTransition>();
@@ -181,6 +218,8 @@ fn on_tag_inserted(
     let state = anim_query.state.into_inner();
     state.ticked = true;
     state.index = first;
+    state.backward = false;
+    state.entering = true;
     state.time = match transition.copied().unwrap_or_default() {
         AnimationTransition::Discrete => Duration::ZERO,
         AnimationTransition::Continuous => state.time,
@@ -199,22 +238,21 @@ fn update_animation_states(
         &AnimationRepeat,
         &mut AnimationEvents,
         Has<AnimationEventsEnabled>,
+        Option<&AnimationSpeed>,
     )>,
 ) {
     let dt = time.delta();
     states
         .par_iter_inner()
-        .for_each(|(entity, anim_query, &repeat, mut events, event_enabled)| {
+        .for_each(|(entity, anim_query, &repeat, mut events, event_enabled, speed)| {
             let Some(sheet) = sheets.get(anim_query.animation.id()) else { return };
             let Some(frame_tag) = sheet.frame_tags.get(anim_query.tag.as_str()) else { return };
 
             let state = anim_query.state.into_inner();
             state.ticked = false;
 
-            let (first, last, incr) = match frame_tag.direction {
-                AnimationDirection::Forward => (*frame_tag.indices.start(), *frame_tag.indices.end(), 1),
-                AnimationDirection::Reverse => (*frame_tag.indices.end(), *frame_tag.indices.start(), -1),
-            };
+            let (first, last, incr) = frame_tag.pass();
+            let ping_pong = frame_tag.direction.is_ping_pong();
 
             if sheet_changes.contains(entity) {
                 let transition = transitions.get(entity).copied();
@@ -226,12 +264,20 @@ fn update_animation_states(
 
                 state.ticked = true;
                 state.index = first;
+                state.backward = false;
+                state.entering = true;
                 state.time = match transition.unwrap_or_default() {
                     AnimationTransition::Discrete => Duration::ZERO,
                     AnimationTransition::Continuous => state.time,
                 };
             }
 
+            // Deferred to here so a tag set on a new entity doesn't trigger twice, once from the tag
+            // insertion and once from the sheet change.
+            if mem::take(&mut state.entering) && event_enabled {
+                trigger_frame_events(&commands, sheet, entity, &anim_query.tag, None, state.index);
+            }
+
             // Reset single-frame bitflags from the previous frame.
             events.set_if_neq(*events & !(AnimationEvents::JUST_HALTED | AnimationEvents::JUST_LOOPED));
             loop {
@@ -243,9 +289,30 @@ fn update_animation_states(
                 };
 
                 let Some(new_time) = state.time.checked_sub(frame.duration) else { break };
-                state.ticked = match repeat {
-                    AnimationRepeat::Halt => {
-                        if state.index == last {
+                let prev_index = state.index;
+                let (pass_end, step) = match state.backward {
+                    false => (last, incr),
+                    true => (first, -incr),
+                };
+
+                state.ticked = if state.index != pass_end {
+                    events.set_if_neq(*events & !AnimationEvents::HALTED | AnimationEvents::ONGOING);
+                    state.index = state.index.wrapping_add_signed(step);
+                    state.time = new_time;
+                    true
+                } else if ping_pong && !state.backward {
+                    // Halfway through a ping-pong cycle; turn around without repeating the last frame.
+                    events.set_if_neq(*events & !AnimationEvents::HALTED | AnimationEvents::ONGOING);
+                    state.backward = true;
+                    if first != last {
+                        state.index = state.index.wrapping_add_signed(-incr);
+                    }
+
+                    state.time = new_time;
+                    true
+                } else {
+                    match repeat {
+                        AnimationRepeat::Halt => {
                             events.set_if_neq(AnimationEvents::HALTED | AnimationEvents::JUST_HALTED);
                             if event_enabled {
                                 commands.command_scope(|mut commands| {
@@ -257,15 +324,8 @@ fn update_animation_states(
                             }
 
                             return
-                        } else {
-                            events.set_if_neq(*events & !AnimationEvents::HALTED | AnimationEvents::ONGOING);
-                            state.index = state.index.wrapping_add_signed(incr);
-                            state.time = new_time;
-                            true
                         }
-                    }
-                    AnimationRepeat::Loop => {
-                        state.index = if state.index == last {
+                        AnimationRepeat::Loop => {
                             events.set_if_neq(AnimationEvents::ONGOING | AnimationEvents::JUST_LOOPED);
                             if event_enabled {
                                 commands.command_scope(|mut commands| {
@@ -275,23 +335,55 @@ fn update_animation_states(
                                     });
                                 });
                             }
-                            first
-                        } else {
-                            events.set_if_neq(*events & !AnimationEvents::HALTED | AnimationEvents::ONGOING);
-                            state.index.wrapping_add_signed(incr)
-                        };
-
-                        state.time = new_time;
-                        true
+
+                            // Ping-pong animations don't repeat the first frame either.
+                            state.backward = false;
+                            state.index = match ping_pong && first != last {
+                                false => first,
+                                true => first.wrapping_add_signed(incr),
+                            };
+                            state.time = new_time;
+                            true
+                        }
                     }
+                };
+
+                if event_enabled && state.index != prev_index {
+                    trigger_frame_events(&commands, sheet, entity, &anim_query.tag, Some(prev_index), state.index);
                 }
             }
 
             // `dt` is added at the end so the first frame has some time to show up in the render world.
-            state.time += dt;
+            state.time += match speed {
+                Some(&speed) => dt.mul_f32(speed.get()),
+                None => dt,
+            };
         });
 }
 
+/// Triggers [`AnimateFrame`] for every event tag covering `index` but not `prev_index`.
+fn trigger_frame_events(
+    commands: &ParallelCommands,
+    sheet: &AnimationSheet,
+    entity: Entity,
+    tag: &AnimationTag,
+    prev_index: Option<usize>,
+    index: usize,
+) {
+    for (event, indices) in &sheet.event_tags {
+        if indices.indices.contains(&index) && !prev_index.is_some_and(|prev| indices.indices.contains(&prev)) {
+            commands.command_scope(|mut commands| {
+                commands.trigger(AnimateFrame {
+                    entity,
+                    tag: (**tag).clone(),
+                    event: event.clone(),
+                    frame: index,
+                });
+            });
+        }
+    }
+}
+
 fn draw_animations(
     param: PainterParam,
     sheets: Res<Assets<AnimationSheet>>,
@@ -333,3 +425,172 @@ pub(super) fn plugin(app: &mut App) {
     )
     .add_observer(on_tag_inserted);
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+    use crate::render::animation::AnimationDirection;
+
+    const FRAME: Duration = Duration::from_millis(100);
+
+    /// Spawns an animation playing `indices` of a 4-frame sheet with the given event tags.
+    fn spawn(
+        indices: RangeInclusive<usize>,
+        direction: AnimationDirection,
+        repeat: AnimationRepeat,
+        event_tags: &[(&str, RangeInclusive<usize>)],
+    ) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+            .init_asset::<AnimationSheet>()
+            .init_resource::<Time>()
+            .add_observer(on_tag_inserted);
+
+        let sheet = app.world_mut().resource_mut::<Assets<AnimationSheet>>().add(AnimationSheet {
+            region: default(),
+            frames: (0..4)
+                .map(|_| AnimationFrame {
+                    region: default(),
+                    offset: Vec2::ZERO,
+                    duration: FRAME,
+                    slices: default(),
+                })
+                .collect(),
+            frame_tags: [("tag".into(), AnimationIndices { indices, direction })].into(),
+            event_tags: event_tags
+                .iter()
+                .map(|(name, indices)| {
+                    ((*name).into(), AnimationIndices {
+                        indices: indices.clone(),
+                        direction: AnimationDirection::Forward,
+                    })
+                })
+                .collect(),
+        });
+
+        let entity = app
+            .world_mut()
+            .spawn((Animation::from(sheet), AnimationTag::new("tag"), repeat, AnimationEventsEnabled))
+            .id();
+        (app, entity)
+    }
+
+    /// Advances time by `dt` and returns the frame index afterwards.
+    fn step(app: &mut App, entity: Entity, dt: Duration) -> usize {
+        let world = app.world_mut();
+        world.resource_mut::<Time>().advance_by(dt);
+        world.run_system_once(update_animation_states).unwrap();
+        world.get::<AnimationState>(entity).unwrap().index
+    }
+
+    /// Plays `indices` of a 4-frame sheet, recording the frame index after each step of `dt`.
+    fn play(
+        indices: RangeInclusive<usize>,
+        direction: AnimationDirection,
+        repeat: AnimationRepeat,
+        dt: Duration,
+        steps: usize,
+    ) -> (Vec<usize>, AnimationEvents) {
+        let (mut app, entity) = spawn(indices, direction, repeat, &[]);
+
+        // The first run only resets the state to the tag's first frame.
+        step(&mut app, entity, Duration::ZERO);
+        let indices = (0..steps).map(|_| step(&mut app, entity, dt)).collect();
+        (indices, *app.world().get::<AnimationEvents>(entity).unwrap())
+    }
+
+    #[test]
+    fn forward() {
+        let (indices, events) = play(0..=3, AnimationDirection::Forward, AnimationRepeat::Loop, FRAME, 9);
+        assert_eq!(indices, [0, 1, 2, 3, 0, 1, 2, 3, 0]);
+        assert!(events.contains(AnimationEvents::ONGOING));
+
+        let (indices, events) = play(0..=3, AnimationDirection::Forward, AnimationRepeat::Halt, FRAME, 6);
+        assert_eq!(indices, [0, 1, 2, 3, 3, 3]);
+        assert!(events.contains(AnimationEvents::HALTED));
+    }
+
+    #[test]
+    fn reverse() {
+        let (indices, ..) = play(0..=3, AnimationDirection::Reverse, AnimationRepeat::Loop, FRAME, 9);
+        assert_eq!(indices, [3, 2, 1, 0, 3, 2, 1, 0, 3]);
+
+        let (indices, events) = play(0..=3, AnimationDirection::Reverse, AnimationRepeat::Halt, FRAME, 6);
+        assert_eq!(indices, [3, 2, 1, 0, 0, 0]);
+        assert!(events.contains(AnimationEvents::HALTED));
+    }
+
+    #[test]
+    fn ping_pong() {
+        let (indices, ..) = play(0..=3, AnimationDirection::PingPong, AnimationRepeat::Loop, FRAME, 13);
+        assert_eq!(indices, [0, 1, 2, 3, 2, 1, 0, 1, 2, 3, 2, 1, 0]);
+
+        let (indices, ..) = play(0..=3, AnimationDirection::PingPongReverse, AnimationRepeat::Loop, FRAME, 13);
+        assert_eq!(indices, [3, 2, 1, 0, 1, 2, 3, 2, 1, 0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn ping_pong_halt() {
+        let (indices, events) = play(0..=3, AnimationDirection::PingPong, AnimationRepeat::Halt, FRAME, 9);
+        assert_eq!(indices, [0, 1, 2, 3, 2, 1, 0, 0, 0]);
+        assert!(events.contains(AnimationEvents::HALTED));
+    }
+
+    #[test]
+    fn single_frame() {
+        for direction in [AnimationDirection::Forward, AnimationDirection::PingPong] {
+            let (indices, ..) = play(2..=2, direction, AnimationRepeat::Loop, FRAME, 4);
+            assert_eq!(indices, [2; 4]);
+
+            let (indices, events) = play(2..=2, direction, AnimationRepeat::Halt, FRAME, 4);
+            assert_eq!(indices, [2; 4]);
+            assert!(events.contains(AnimationEvents::HALTED));
+        }
+    }
+
+    #[test]
+    fn long_delta_skips_frames() {
+        // Frame time is accumulated after stepping, so frames advance one step late: 0ms, 250ms
+        // (0 -> 2), 500ms (2 -> 3 -> 2 -> 1), then 750ms (1 -> 0 -> 1).
+        let (indices, ..) = play(0..=3, AnimationDirection::PingPong, AnimationRepeat::Loop, FRAME * 5 / 2, 4);
+        assert_eq!(indices, [0, 2, 1, 1]);
+    }
+
+    #[test]
+    fn frame_events_over_skipped_frames() {
+        #[derive(Resource, Default)]
+        struct Fired(Vec<(String, usize)>);
+
+        let (mut app, entity) = spawn(0..=3, AnimationDirection::Forward, AnimationRepeat::Loop, &[
+            ("start", 0..=0),
+            ("c", 2..=2),
+            ("b", 2..=3),
+            ("a", 1..=1),
+        ]);
+        app.init_resource::<Fired>()
+            .add_observer(|frame: On<AnimateFrame>, mut fired: ResMut<Fired>| {
+                let frame = frame.event();
+                fired.0.push((frame.event.clone(), frame.frame));
+            });
+
+        // Entering the tag triggers its first frame once, even though both the tag insertion and
+        // the sheet change reset it.
+        step(&mut app, entity, Duration::ZERO);
+        // Time is accumulated one step late, so the second step skips 0 -> 1 -> 2, and the third
+        // 2 -> 3 -> 0 -> 1. Frame 3 is still within `b`, so it doesn't trigger again.
+        for _ in 0..3 {
+            step(&mut app, entity, FRAME * 5 / 2);
+        }
+
+        let fired = app
+            .world()
+            .resource::<Fired>()
+            .0
+            .iter()
+            .map(|(event, frame)| (event.as_str(), *frame))
+            .collect::<Vec<_>>();
+        assert_eq!(fired, [("start", 0), ("a", 1), ("b", 2), ("c", 2), ("start", 0), ("a", 1)]);
+    }
+}
@@ -1,20 +1,66 @@
 use guillotiere::{SimpleAtlasAllocator, euclid::Box2D, size2};
 
-use crate::prelude::*;
+use crate::{prelude::*, render::atlas::AtlasRegion};
 
 #[derive(Resource, Debug, Default)]
 pub struct AtlasPages {
     pages: Vec<AtlasPage>,
+    unloaded: Vec<Handle<Image>>,
+}
+
+impl AtlasPages {
+    /// Returns the name of the page with the given texture, if it was created for named requests.
+    pub fn page_name(&self, texture: impl Into<AssetId<Image>>) -> Option<&str> {
+        let texture = texture.into();
+        self.pages
+            .iter()
+            .find(|page| page.info.texture.id() == texture)
+            .and_then(|page| page.name.as_deref())
+    }
+
+    /// Removes the page with the given texture, freeing its image along with every [`AtlasRegion`]
+    /// packed in it, the next time atlas requests are handled in [`Update`]. Returns `false` if
+    /// there's no such page.
+    ///
+    /// Regions loaded from a plain path that something still holds a handle to are reloaded, i.e.
+    /// packed into another page. Labeled regions, such as the tiles of an LDtk world's tilesets,
+    /// can't be reloaded on their own; their handles stay valid but resolve to nothing, so make
+    /// sure nothing still draws them.
+    pub fn unload_page(&mut self, texture: impl Into<AssetId<Image>>) -> bool {
+        let texture = texture.into();
+        let Some(index) = self.pages.iter().position(|page| page.info.texture.id() == texture) else { return false };
+
+        let page = self.pages.swap_remove(index);
+        self.unloaded.push(page.info.texture);
+        true
+    }
+
+    /// Removes every page created for the given name; see [`unload_page()`](Self::unload_page).
+    /// Returns how many pages were removed.
+    pub fn unload_named(&mut self, name: &str) -> usize {
+        let (unloaded, kept) = mem::take(&mut self.pages)
+            .into_iter()
+            .partition::<Vec<_>, _>(|page| page.name.as_deref() == Some(name));
+
+        self.pages = kept;
+        self.unloaded.extend(unloaded.iter().map(|page| page.info.texture.clone()));
+        unloaded.len()
+    }
 }
 
 pub struct AtlasPage {
     packer: SimpleAtlasAllocator,
     info: PageInfo,
+    /// Only requests for this page name are packed here, or unnamed requests if `None`.
+    name: Option<String>,
 }
 
 impl Debug for AtlasPage {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("AtlasPage").field("texture", &self.info.texture).finish_non_exhaustive()
+        f.debug_struct("AtlasPage")
+            .field("texture", &self.info.texture)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
     }
 }
 
@@ -45,12 +91,32 @@ impl AtlasInfo {
         let [uv0, uv1] = self.uvs();
         [vec2(uv0.x, uv1.y), vec2(uv1.x, uv1.y), vec2(uv1.x, uv0.y), vec2(uv0.x, uv0.y)]
     }
+
+    /// Same as [`uv_corners()`](Self::uv_corners), but shrunk by `inset` texels on each side so
+    /// sampling at fractional scales doesn't bleed into neighboring regions of the page.
+    pub fn uv_corners_inset(&self, inset: f32) -> [Vec2; 4] {
+        let inset = inset / self.page.texture_size.as_vec2();
+        let [uv0, uv1] = self.uvs();
+        let [uv0, uv1] = [uv0 + inset, uv1 - inset];
+        [vec2(uv0.x, uv1.y), vec2(uv1.x, uv1.y), vec2(uv1.x, uv0.y), vec2(uv0.x, uv0.y)]
+    }
+}
+
+/// Mirrors corners returned by [`AtlasInfo::uv_corners()`] horizontally and/or vertically, so the
+/// region is drawn flipped without touching vertex positions.
+pub fn flip_uv_corners([bl, br, tr, tl]: [Vec2; 4], flip: BVec2) -> [Vec2; 4] {
+    match (flip.x, flip.y) {
+        (false, false) => [bl, br, tr, tl],
+        (true, false) => [br, bl, tl, tr],
+        (false, true) => [tl, tr, br, bl],
+        (true, true) => [tr, tl, bl, br],
+    }
 }
 
 pub type AtlasInfoSender = async_channel::Sender<AtlasInfo>;
 
 #[derive(Debug, Clone)]
-pub struct AtlasRequester(async_channel::Sender<(Image, AtlasInfoSender)>);
+pub struct AtlasRequester(async_channel::Sender<(Image, Option<String>, AtlasInfoSender)>);
 impl FromWorld for AtlasRequester {
     fn from_world(world: &mut World) -> Self {
         world.resource::<AtlasRequesters>().new_sender()
@@ -59,16 +125,22 @@ impl FromWorld for AtlasRequester {
 
 impl AtlasRequester {
     pub async fn request(&self, image: Image) -> Result<AtlasInfo> {
+        self.request_into(None, image).await
+    }
+
+    /// Packs the image into a page dedicated to `page_name` so related sprites get batched
+    /// together, falling back to shared pages if the dedicated pages are full.
+    pub async fn request_into(&self, page_name: impl Into<Option<String>>, image: Image) -> Result<AtlasInfo> {
         let (sender, receiver) = async_channel::bounded(1);
-        self.0.send((image, sender)).await?;
+        self.0.send((image, page_name.into(), sender)).await?;
         Ok(receiver.recv().await?)
     }
 }
 
 #[derive(Resource, Debug)]
 pub struct AtlasRequesters {
-    sender: async_channel::Sender<(Image, AtlasInfoSender)>,
-    receiver: async_channel::Receiver<(Image, AtlasInfoSender)>,
+    sender: async_channel::Sender<(Image, Option<String>, AtlasInfoSender)>,
+    receiver: async_channel::Receiver<(Image, Option<String>, AtlasInfoSender)>,
 }
 
 impl AtlasRequesters {
@@ -81,14 +153,26 @@ impl AtlasRequesters {
 struct AtlasRequests(Vec<(AssetId<Image>, Vec<u8>, URect)>);
 
 fn handle_atlas_requests(
+    atlas: ResMut<AtlasPages>,
+    requests: ResMut<AtlasRequests>,
+    requesters: Res<AtlasRequesters>,
+    device: Res<RenderDevice>,
+    images: ResMut<Assets<Image>>,
+) -> Result {
+    let max_size = device.limits().max_texture_dimension_2d.min(8192);
+    pack_atlas_requests(max_size, atlas, requests, requesters, images)
+}
+
+/// Packs pending requests into pages of `max_size` squared, split from [`handle_atlas_requests()`]
+/// so it runs without a render device.
+fn pack_atlas_requests(
+    max_size: u32,
     mut atlas: ResMut<AtlasPages>,
     mut requests: ResMut<AtlasRequests>,
     requesters: Res<AtlasRequesters>,
-    device: Res<RenderDevice>,
     mut images: ResMut<Assets<Image>>,
 ) -> Result {
-    let max_size = device.limits().max_texture_dimension_2d.min(8192);
-    'request: while let Ok((image, mut payload_sender)) = requesters.receiver.try_recv() {
+    'request: while let Ok((image, page_name, mut payload_sender)) = requesters.receiver.try_recv() {
         let size = image.size();
         if size.x > max_size || size.y > max_size {
             Err(format!("Sprite of size `{size}` exceeds `{max_size}`"))?
@@ -117,17 +201,40 @@ fn handle_atlas_requests(
             }
         };
 
-        for page in &mut atlas.pages {
-            match try_pack(page, data, payload_sender) {
-                Ok(()) => continue 'request,
-                Err((failed_data, failed_sender)) => {
-                    data = failed_data;
-                    payload_sender = failed_sender;
+        let mut new_page_name = None;
+        if let Some(name) = page_name {
+            let mut exists = false;
+            for page in atlas.pages.iter_mut().filter(|page| page.name.as_ref() == Some(&name)) {
+                exists = true;
+                match try_pack(page, data, payload_sender) {
+                    Ok(()) => continue 'request,
+                    Err((failed_data, failed_sender)) => {
+                        data = failed_data;
+                        payload_sender = failed_sender;
+                    }
+                }
+            }
+
+            // Dedicate a new page to the name if there isn't one yet, otherwise fall back to shared pages.
+            if !exists {
+                new_page_name = Some(name);
+            }
+        }
+
+        if new_page_name.is_none() {
+            for page in atlas.pages.iter_mut().filter(|page| page.name.is_none()) {
+                match try_pack(page, data, payload_sender) {
+                    Ok(()) => continue 'request,
+                    Err((failed_data, failed_sender)) => {
+                        data = failed_data;
+                        payload_sender = failed_sender;
+                    }
                 }
             }
         }
 
         let mut new_page = AtlasPage {
+            name: new_page_name,
             packer: SimpleAtlasAllocator::new(size2(max_size as i32, max_size as i32)),
             info: PageInfo {
                 texture: images.add(Image::new_fill(
@@ -152,6 +259,41 @@ fn handle_atlas_requests(
     Ok(())
 }
 
+fn remove_unloaded_pages(
+    server: Res<AssetServer>,
+    mut atlas: ResMut<AtlasPages>,
+    mut requests: ResMut<AtlasRequests>,
+    mut images: ResMut<Assets<Image>>,
+    mut regions: ResMut<Assets<AtlasRegion>>,
+) {
+    for texture in mem::take(&mut atlas.unloaded) {
+        let texture = texture.id();
+        requests.0.retain(|&(page, ..)| page != texture);
+
+        let unloaded_regions = regions
+            .iter()
+            .filter_map(|(id, region)| (region.page.texture.id() == texture).then_some(id))
+            .collect::<Vec<_>>();
+        for id in unloaded_regions {
+            regions.remove(id);
+
+            // The asset server still considers the region loaded as long as anything holds it, and
+            // would hand out handles to the removed asset instead of loading it again.
+            if !server.is_loaded(id) {
+                continue
+            }
+
+            match server.get_path(id) {
+                Some(path) if path.label().is_none() => server.reload(path),
+                Some(path) => warn!("Unloaded atlas region {path} can't be reloaded while still in use"),
+                None => {}
+            }
+        }
+
+        images.remove(texture);
+    }
+}
+
 #[derive(Resource, Debug, Default)]
 struct ExtractedAtlasRequests(Vec<(AssetId<Image>, Vec<u8>, URect)>);
 
@@ -194,7 +336,7 @@ pub(super) fn plugin(app: &mut App) {
     app.init_resource::<AtlasPages>()
         .init_resource::<AtlasRequests>()
         .insert_resource(AtlasRequesters { sender, receiver })
-        .add_systems(Update, handle_atlas_requests);
+        .add_systems(Update, (handle_atlas_requests, remove_unloaded_pages).chain());
 
     if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
         render_app
@@ -203,3 +345,162 @@ pub(super) fn plugin(app: &mut App) {
             .add_systems(Render, prepare_atlas_requests.in_set(RenderSystems::PrepareResources));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+
+    use bevy::ecs::system::RunSystemOnce;
+    use futures_lite::future::{block_on, poll_once};
+
+    use super::*;
+    use crate::render::atlas::AtlasInfo;
+
+    fn add_page(app: &mut App, name: Option<&str>) -> (Handle<Image>, Handle<AtlasRegion>) {
+        let world = app.world_mut();
+        let texture = world.resource_mut::<Assets<Image>>().add(Image::default());
+        let info = PageInfo {
+            texture: texture.clone(),
+            texture_size: UVec2::splat(64),
+        };
+
+        let region = world.resource_mut::<Assets<AtlasRegion>>().add(AtlasRegion {
+            info: AtlasInfo {
+                page: info.clone(),
+                rect: URect::new(1, 1, 9, 9),
+            },
+        });
+
+        world.resource_mut::<AtlasPages>().pages.push(AtlasPage {
+            packer: SimpleAtlasAllocator::new(size2(64, 64)),
+            info,
+            name: name.map(Into::into),
+        });
+        (texture, region)
+    }
+
+    #[test]
+    fn unload_named_pages() {
+        let mut app = App::new();
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<AtlasRegion>()
+            .init_resource::<AtlasPages>()
+            .init_resource::<AtlasRequests>();
+
+        let (level_texture, level_region) = add_page(&mut app, Some("level"));
+        let (shared_texture, shared_region) = add_page(&mut app, None);
+        app.world_mut()
+            .resource_mut::<AtlasRequests>()
+            .0
+            .push((level_texture.id(), vec![0; 8 * 8 * 4], URect::new(1, 1, 9, 9)));
+
+        assert_eq!(app.world().resource::<AtlasPages>().page_name(&level_texture), Some("level"));
+        assert_eq!(app.world_mut().resource_mut::<AtlasPages>().unload_named("level"), 1);
+        app.world_mut().run_system_once(remove_unloaded_pages).unwrap();
+
+        let world = app.world();
+        assert!(world.resource::<Assets<AtlasRegion>>().get(&level_region).is_none());
+        assert!(world.resource::<Assets<Image>>().get(&level_texture).is_none());
+        assert!(world.resource::<AtlasRequests>().0.is_empty());
+        assert_eq!(world.resource::<AtlasPages>().page_name(&level_texture), None);
+
+        assert!(world.resource::<Assets<AtlasRegion>>().get(&shared_region).is_some());
+        assert!(world.resource::<Assets<Image>>().get(&shared_texture).is_some());
+        assert_eq!(world.resource::<AtlasPages>().pages.len(), 1);
+    }
+
+    #[test]
+    fn named_requests_share_a_page() {
+        let mut app = App::new();
+        let (sender, receiver) = async_channel::bounded(8);
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_resource::<AtlasPages>()
+            .init_resource::<AtlasRequests>()
+            .insert_resource(AtlasRequesters { sender, receiver });
+
+        let requester = app.world().resource::<AtlasRequesters>().new_sender();
+        let image = || {
+            Image::new_fill(
+                Extent3d {
+                    width: 8,
+                    height: 8,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &[255; 4],
+                TextureFormat::Rgba8UnormSrgb,
+                RenderAssetUsages::MAIN_WORLD,
+            )
+        };
+
+        let mut tiles_a = pin!(requester.request_into("tiles".to_string(), image()));
+        let mut tiles_b = pin!(requester.request_into("tiles".to_string(), image()));
+        let mut shared = pin!(requester.request(image()));
+        assert!(block_on(poll_once(tiles_a.as_mut())).is_none());
+        assert!(block_on(poll_once(tiles_b.as_mut())).is_none());
+        assert!(block_on(poll_once(shared.as_mut())).is_none());
+
+        app.world_mut()
+            .run_system_once(
+                |atlas: ResMut<AtlasPages>, requests: ResMut<AtlasRequests>, requesters: Res<AtlasRequesters>, images: ResMut<Assets<Image>>| {
+                    pack_atlas_requests(64, atlas, requests, requesters, images)
+                },
+            )
+            .unwrap()
+            .unwrap();
+
+        let (tiles_a, tiles_b, shared) = (block_on(tiles_a).unwrap(), block_on(tiles_b).unwrap(), block_on(shared).unwrap());
+        let pages = app.world().resource::<AtlasPages>();
+        assert_eq!(tiles_a.page.texture, tiles_b.page.texture);
+        assert_ne!(tiles_a.rect, tiles_b.rect);
+        assert_eq!(pages.page_name(&tiles_a.page.texture), Some("tiles"));
+
+        assert_ne!(shared.page.texture, tiles_a.page.texture);
+        assert_eq!(pages.page_name(&shared.page.texture), None);
+        assert_eq!(pages.pages.len(), 2);
+    }
+
+    /// An 8x4 region at the top-left corner of a 16x16 page.
+    fn region_info() -> AtlasInfo {
+        AtlasInfo {
+            page: PageInfo {
+                texture: default(),
+                texture_size: UVec2::splat(16),
+            },
+            rect: URect::new(0, 0, 8, 4),
+        }
+    }
+
+    #[test]
+    fn flipped_uv_corners() {
+        let corners = region_info().uv_corners();
+        let [bl, br, tr, tl] = [vec2(0., 0.25), vec2(0.5, 0.25), vec2(0.5, 0.), vec2(0., 0.)];
+        assert_eq!(corners, [bl, br, tr, tl]);
+
+        assert_eq!(flip_uv_corners(corners, BVec2::FALSE), corners);
+        assert_eq!(flip_uv_corners(corners, BVec2::new(true, false)), [br, bl, tl, tr]);
+        assert_eq!(flip_uv_corners(corners, BVec2::new(false, true)), [tl, tr, br, bl]);
+        assert_eq!(flip_uv_corners(corners, BVec2::TRUE), [tr, tl, bl, br]);
+
+        for flip in [BVec2::new(true, false), BVec2::new(false, true), BVec2::TRUE] {
+            assert_eq!(flip_uv_corners(flip_uv_corners(corners, flip), flip), corners);
+        }
+    }
+
+    #[test]
+    fn inset_uv_corners() {
+        let info = region_info();
+        assert_eq!(info.uv_corners_inset(0.), info.uv_corners());
+
+        // Half a texel of a 16x16 page is 1/32 in UV space.
+        let [uv0, uv1] = [vec2(0.03125, 0.03125), vec2(0.46875, 0.21875)];
+        assert_eq!(info.uv_corners_inset(0.5), [
+            vec2(uv0.x, uv1.y),
+            vec2(uv1.x, uv1.y),
+            vec2(uv1.x, uv0.y),
+            vec2(uv0.x, uv0.y)
+        ]);
+    }
+}
@@ -1,3 +1,5 @@
+use serde::Serialize;
+
 use crate::{
     prelude::*,
     render::atlas::{AtlasInfo, AtlasRequester, AtlasRequesters},
@@ -9,6 +11,12 @@ pub struct AtlasRegion {
     pub info: AtlasInfo,
 }
 
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AtlasRegionSettings {
+    /// Name of the atlas page to pack the region into; see [`AtlasRequester::request_into()`].
+    pub page: Option<String>,
+}
+
 #[derive(Clone)]
 pub struct AtlasRegionLoader {
     requester: AtlasRequester,
@@ -16,10 +24,10 @@ pub struct AtlasRegionLoader {
 
 impl AssetLoader for AtlasRegionLoader {
     type Asset = AtlasRegion;
-    type Settings = ();
+    type Settings = AtlasRegionSettings;
     type Error = BevyError;
 
-    async fn load(&self, _: &mut dyn Reader, _: &Self::Settings, load_context: &mut LoadContext<'_>) -> Result<Self::Asset, Self::Error> {
+    async fn load(&self, _: &mut dyn Reader, settings: &Self::Settings, load_context: &mut LoadContext<'_>) -> Result<Self::Asset, Self::Error> {
         let path = load_context.asset_path().clone();
         let image = load_context
             .loader()
@@ -30,7 +38,7 @@ impl AssetLoader for AtlasRegionLoader {
             })
             .load(path)
             .await?;
-        let info = self.requester.request(image.take()).await?;
+        let info = self.requester.request_into(settings.page.clone(), image.take()).await?;
         Ok(AtlasRegion { info })
     }
 
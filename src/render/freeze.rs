@@ -0,0 +1,37 @@
+use crate::prelude::*;
+
+/// Dev-only switch that stops [painters](crate::render::painter::Painter) from being extracted to
+/// the render world and [`Tilemap`](crate::world::Tilemap) chunks from being remeshed, so a single
+/// frame's batches stay on screen for inspection while the main world keeps running. Everything
+/// else Bevy extracts itself keeps updating, e.g. cameras and chunk transforms, so parallax still
+/// moves frozen chunks.
+///
+/// [`KeyCode::F9`] toggles freezing, and [`KeyCode::F10`] extracts a single frame while frozen.
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource, Debug, Default, FromWorld, Clone, PartialEq)]
+pub struct RenderFreeze {
+    pub frozen: bool,
+    /// Set for the frame a single extraction is requested in.
+    pub step: bool,
+}
+
+impl RenderFreeze {
+    /// Whether extraction should be skipped this frame.
+    pub fn is_skipping(self) -> bool {
+        self.frozen && !self.step
+    }
+}
+
+fn toggle_render_freeze(keys: Res<ButtonInput<KeyCode>>, mut freeze: ResMut<RenderFreeze>) {
+    if keys.just_pressed(KeyCode::F9) {
+        freeze.frozen = !freeze.frozen;
+        info!("Render extraction {}", if freeze.frozen { "frozen" } else { "unfrozen" });
+    }
+
+    let step = freeze.frozen && keys.just_pressed(KeyCode::F10);
+    freeze.set_if_neq(RenderFreeze { step, ..*freeze });
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RenderFreeze>().add_systems(PreUpdate, toggle_render_freeze);
+}
@@ -1,11 +1,15 @@
 mod attribute;
+#[cfg(feature = "dev")]
+mod freeze;
 pub use attribute::*;
+#[cfg(feature = "dev")]
+pub use freeze::*;
 
 pub mod animation;
 pub mod atlas;
 pub mod painter;
 
-use crate::{math::Transform2d, prelude::*};
+use crate::{math::Transform2d, prelude::*, world::LevelBounds};
 
 pub const MAIN_LAYER: RenderLayers = RenderLayers::layer(0);
 pub const OUTPUT_LAYER: RenderLayers = RenderLayers::layer(1);
@@ -37,6 +41,148 @@ pub struct OutputCamera;
 #[reflect(Component, Debug, Default, FromWorld, Clone)]
 pub struct PixelatedCanvas;
 
+/// Makes the [`MainCamera`] ease towards its [`CameraTarget`] instead of snapping to it.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Debug, Default, FromWorld, Clone)]
+pub struct CameraFollow {
+    /// Exponential easing rate, per second. Non-positive values snap immediately.
+    pub lerp: f32,
+    /// Size of the rectangle centered on the camera that the target may move freely in without
+    /// moving the camera.
+    pub deadzone: Vec2,
+}
+
+impl Default for CameraFollow {
+    fn default() -> Self {
+        Self {
+            lerp: 8.,
+            deadzone: Vec2::ZERO,
+        }
+    }
+}
+
+/// Restricts where the [`MainCamera`] may move, applied after following its [`CameraTarget`].
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[reflect(Component, Debug, Clone)]
+pub enum CameraConfines {
+    /// Keep the visible viewport inside the [`LevelBounds`]. On axes where the level is smaller
+    /// than the viewport, the camera is centered on the level instead.
+    Bounds,
+    /// Keep the camera at a fixed position, regardless of its target.
+    Fixed(Vec2),
+}
+
+/// Screen shake applied on top of [`MainCamera::snapped_pos()`], rounded to whole pixels. Shakes
+/// from multiple entities stack additively, and each shake entity is despawned once its `duration`
+/// has elapsed.
+#[derive(Component, Reflect, Debug, Clone, Copy)]
+#[require(CameraShakeElapsed)]
+#[reflect(Component, Debug, Clone)]
+pub struct CameraShake {
+    /// Maximum offset in pixels, decaying towards zero over `duration`.
+    pub amplitude: f32,
+    /// How many times per second the offset changes direction, roughly.
+    pub frequency: f32,
+    pub duration: Duration,
+}
+
+impl CameraShake {
+    pub const DEFAULT_FREQUENCY: f32 = 30.;
+
+    /// A short shake with the default frequency, to be spawned as its own entity.
+    pub fn burst(amplitude: f32, duration: Duration) -> Self {
+        Self {
+            amplitude,
+            frequency: Self::DEFAULT_FREQUENCY,
+            duration,
+        }
+    }
+
+    fn offset(self, seed: u64, elapsed: Duration) -> Vec2 {
+        // Seeded 1D value noise, so each shake entity has its own deterministic pattern.
+        fn noise(seed: u64, t: f32) -> f32 {
+            fn hash(seed: u64, i: i64) -> f32 {
+                let mut x = seed ^ (i as u64).wrapping_mul(0x9E3779B97F4A7C15);
+                x = (x ^ (x >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+                x = (x ^ (x >> 27)).wrapping_mul(0x94D049BB133111EB);
+                x ^= x >> 31;
+                (x >> 40) as f32 / (1u64 << 24) as f32 * 2. - 1.
+            }
+
+            let i = t.floor();
+            let f = t - i;
+            let (a, b) = (hash(seed, i as i64), hash(seed, i as i64 + 1));
+            a + (b - a) * f * f * (3. - 2. * f)
+        }
+
+        if self.duration.is_zero() {
+            return Vec2::ZERO
+        }
+
+        let progress = elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        let decay = (1. - progress).clamp(0., 1.).powi(2);
+        let t = elapsed.as_secs_f32() * self.frequency;
+
+        vec2(noise(seed, t), noise(!seed, t)) * self.amplitude * decay
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, Copy)]
+struct CameraShakeElapsed(Duration);
+
+fn update_camera_shakes(mut commands: Commands, time: Res<Time>, shakes: Query<(Entity, &CameraShake, &mut CameraShakeElapsed)>) {
+    let dt = time.delta();
+    for (entity, shake, mut elapsed) in shakes {
+        if elapsed.0 >= shake.duration {
+            commands.entity(entity).try_despawn();
+        } else {
+            elapsed.0 += dt;
+        }
+    }
+}
+
+/// How many physical window pixels a single pixel of the [`PixelatedCanvas`] spans on each axis.
+/// Values below `1` are treated as `1`.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq, Eq, Deref, DerefMut)]
+#[reflect(Resource, Debug, Default, FromWorld, Clone, PartialEq)]
+pub struct PixelScale(pub u32);
+
+impl PixelScale {
+    pub fn get(self) -> u32 {
+        self.0.max(1)
+    }
+
+    /// Size of the [`PixelatedCanvas`] in its own pixels for a window of `window_size` physical
+    /// pixels.
+    pub fn canvas_size(self, window_size: UVec2) -> UVec2 {
+        (window_size / self.get()).max(UVec2::splat(2))
+    }
+
+    /// Maps a physical `cursor` position in a window of `window_size` physical pixels to the
+    /// [`PixelatedCanvas`], which is centered in the window. Returns `None` if the cursor is
+    /// outside of the canvas, e.g. on the leftover border when the window size isn't a multiple
+    /// of the scale.
+    pub fn window_to_canvas(self, cursor: Vec2, window_size: UVec2) -> Option<Vec2> {
+        let canvas_size = self.canvas_size(window_size);
+        let offset = (window_size.as_vec2() - (canvas_size * self.get()).as_vec2()) / 2.;
+        let canvas_pos = (cursor - offset) / self.get() as f32;
+
+        (canvas_pos.cmpge(Vec2::ZERO).all() && canvas_pos.cmplt(canvas_size.as_vec2()).all()).then_some(canvas_pos)
+    }
+}
+
+impl Default for PixelScale {
+    fn default() -> Self {
+        Self(4)
+    }
+}
+
+/// Whether rendering systems should sort their hash-map-driven outputs by stable keys, e.g. asset
+/// IDs, so draw order is reproducible across runs. Off by default as sorting costs extra time.
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, Deref, DerefMut)]
+#[reflect(Resource, Debug, Default, FromWorld, Clone, PartialEq)]
+pub struct DeterministicRender(pub bool);
+
 fn spawn_cameras(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
     let image = images.add(Image::new_target_texture(2, 2, ViewTarget::TEXTURE_FORMAT_HDR));
     commands.spawn((
@@ -71,18 +217,21 @@ fn spawn_cameras(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
 }
 
 fn update_canvas(
+    scale: Res<PixelScale>,
     window: Single<&Window, With<PrimaryWindow>>,
     mut images: ResMut<Assets<Image>>,
     pixelated_camera: Single<&Camera, With<MainCamera>>,
     main_camera: Single<&Transform, (With<OutputCamera>, Without<PixelatedCanvas>)>,
     mut pixelated_canvas: Single<&mut Transform, With<PixelatedCanvas>>,
 ) {
+    let scale = scale.get();
     if let RenderTarget::Image(ImageRenderTarget { handle, .. }) = &pixelated_camera.target
         && let Some(canvas_image) = images.get_mut_untracked(handle)
     {
+        let canvas_size = PixelScale(scale).canvas_size(window.physical_size());
         let size = Extent3d {
-            width: (window.physical_width() / 4).max(2),
-            height: (window.physical_height() / 4).max(2),
+            width: canvas_size.x,
+            height: canvas_size.y,
             depth_or_array_layers: 1,
         };
 
@@ -95,16 +244,18 @@ fn update_canvas(
     let trns = **main_camera;
     **pixelated_canvas = Transform {
         translation: trns.translation.with_z(0.),
-        scale: trns.scale * 4.,
+        scale: trns.scale * scale as f32,
         ..trns
     };
 }
 
 fn move_camera_to_target(
+    time: Res<Time>,
     targets: Query<(Entity, &CameraTarget)>,
     transforms: Query<(&Transform2d, Option<&ChildOf>)>,
-    mut camera_trns: Single<&mut MainCamera>,
+    camera: Single<(&mut MainCamera, Option<&CameraFollow>)>,
 ) {
+    let (mut camera_trns, follow) = camera.into_inner();
     let Some(target) = targets.into_iter().max_by_key(|(.., target)| target.priority).map(|(entity, ..)| entity) else { return };
     let Ok((trns, mut child_of)) = transforms.get(target) else { return };
 
@@ -116,25 +267,125 @@ fn move_camera_to_target(
         child_of = parent_child_of;
     }
 
-    camera_trns.pos = trns.translation.truncate();
+    let target_pos = trns.translation.truncate();
+    camera_trns.pos = match follow {
+        Some(&CameraFollow { lerp, deadzone }) if lerp > 0. => {
+            let delta = target_pos - camera_trns.pos;
+            let half_deadzone = deadzone.abs() / 2.;
+            let outside = delta - delta.clamp(-half_deadzone, half_deadzone);
+
+            // Frame-rate independent exponential easing.
+            camera_trns.pos + outside * (1. - (-lerp * time.delta_secs()).exp())
+        }
+        _ => target_pos,
+    };
+}
+
+fn confine_camera(
+    scale: Res<PixelScale>,
+    bounds: Option<Res<LevelBounds>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&mut MainCamera, &CameraConfines)>,
+) {
+    let half_viewport = scale.canvas_size(window.physical_size()).as_vec2() / 2.;
+    for (mut camera, &confines) in cameras {
+        match confines {
+            CameraConfines::Bounds => {
+                let Some(ref bounds) = bounds else { continue };
+                let confine = |pos: f32, half_viewport: f32, size: f32| match size > half_viewport * 2. {
+                    true => pos.clamp(half_viewport, size - half_viewport),
+                    false => size / 2.,
+                };
+
+                camera.pos = vec2(
+                    confine(camera.pos.x, half_viewport.x, bounds.x),
+                    confine(camera.pos.y, half_viewport.y, bounds.y),
+                );
+            }
+            CameraConfines::Fixed(pos) => camera.pos = pos,
+        }
+    }
 }
 
-fn snap_camera(camera_trns: Single<(&MainCamera, &mut Transform)>) {
+fn snap_camera(camera_trns: Single<(&MainCamera, &mut Transform)>, shakes: Query<(Entity, &CameraShake, &CameraShakeElapsed)>) {
     let (&camera, mut trns) = camera_trns.into_inner();
-    trns.translation = camera.pos.extend(trns.translation.z);
+    let shake = shakes
+        .iter()
+        .map(|(entity, &shake, &CameraShakeElapsed(elapsed))| shake.offset(entity.to_bits(), elapsed))
+        .sum::<Vec2>();
+
+    // Both terms are whole pixels, so the shake doesn't introduce sub-pixel jittering.
+    trns.translation = (camera.snapped_pos() + shake.round()).extend(trns.translation.z);
 }
 
 pub fn plugin(app: &mut App) {
     use bevy::transform::systems::*;
 
-    app.add_plugins((animation::plugin, atlas::plugin, painter::plugin))
-        .add_systems(Startup, spawn_cameras)
-        .add_systems(Update, update_canvas)
-        .add_systems(
-            PostUpdate,
-            (move_camera_to_target, snap_camera)
-                .chain()
-                .before(mark_dirty_trees)
-                .in_set(TransformSystems::Propagate),
-        );
+    app.add_plugins((
+        animation::plugin,
+        atlas::plugin,
+        painter::plugin,
+        #[cfg(feature = "dev")]
+        freeze::plugin,
+    ))
+    .init_resource::<PixelScale>()
+    .init_resource::<DeterministicRender>()
+    .add_systems(Startup, spawn_cameras)
+    .add_systems(Update, (update_canvas, update_camera_shakes))
+    .add_systems(
+        PostUpdate,
+        (move_camera_to_target, confine_camera, snap_camera)
+            .chain()
+            .before(mark_dirty_trees)
+            .in_set(TransformSystems::Propagate),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::RunSystemOnce, window::WindowResolution};
+
+    use super::*;
+
+    fn confine(pos: Vec2, bounds: Vec2, confines: CameraConfines) -> Vec2 {
+        let mut world = World::new();
+        world.insert_resource(PixelScale(2));
+        world.insert_resource(LevelBounds(bounds));
+        world.spawn((
+            Window {
+                resolution: WindowResolution::new(200, 120),
+                ..default()
+            },
+            PrimaryWindow,
+        ));
+
+        // The canvas is 100x60 pixels, so the camera keeps 50x30 away from the level edges.
+        let camera = world.spawn((MainCamera { pos }, confines)).id();
+        world.run_system_once(confine_camera).unwrap();
+        world.get::<MainCamera>(camera).unwrap().pos
+    }
+
+    #[test]
+    fn confine_to_bounds_near_edges() {
+        let bounds = vec2(400., 300.);
+        assert_eq!(confine(vec2(10., 150.), bounds, CameraConfines::Bounds), vec2(50., 150.));
+        assert_eq!(confine(vec2(395., 150.), bounds, CameraConfines::Bounds), vec2(350., 150.));
+        assert_eq!(confine(vec2(200., 5.), bounds, CameraConfines::Bounds), vec2(200., 30.));
+        assert_eq!(confine(vec2(200., 299.), bounds, CameraConfines::Bounds), vec2(200., 270.));
+        assert_eq!(confine(vec2(-20., 320.), bounds, CameraConfines::Bounds), vec2(50., 270.));
+        assert_eq!(confine(vec2(200., 150.), bounds, CameraConfines::Bounds), vec2(200., 150.));
+    }
+
+    #[test]
+    fn confine_centers_small_levels() {
+        assert_eq!(confine(vec2(5., 5.), vec2(80., 40.), CameraConfines::Bounds), vec2(40., 20.));
+        // Only the axis that's smaller than the viewport is centered.
+        assert_eq!(confine(vec2(5., 5.), vec2(400., 40.), CameraConfines::Bounds), vec2(50., 20.));
+    }
+
+    #[test]
+    fn confine_fixed() {
+        let fixed = vec2(12., 34.);
+        assert_eq!(confine(vec2(200., 150.), vec2(400., 300.), CameraConfines::Fixed(fixed)), fixed);
+    }
 }
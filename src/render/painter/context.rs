@@ -1,7 +1,7 @@
 use crate::{
     prelude::*,
     render::{
-        atlas::AtlasRegion,
+        atlas::{AtlasRegion, flip_uv_corners},
         painter::{Blending, Painter, PainterQuads, Vertex},
     },
 };
@@ -36,6 +36,7 @@ impl<'a> PainterParam<'a> {
             blend: Blending::Normal,
             layer: 0.,
             color: LinearRgba::WHITE,
+            flip: BVec2::FALSE,
         }
     }
 }
@@ -48,6 +49,8 @@ pub struct PainterContext<'a> {
     pub blend: Blending,
     pub layer: f32,
     pub color: LinearRgba,
+    /// Mirrors the texture of drawn regions on each axis, independent of the transform.
+    pub flip: BVec2,
 }
 
 impl<'a> PainterContext<'a> {
@@ -61,7 +64,7 @@ impl<'a> PainterContext<'a> {
         let size = size.unwrap_or(region.rect.size().as_vec2());
         let half_size = size / 2.;
         let center = -*anchor * size;
-        let [uv0, uv1, uv2, uv3] = region.uv_corners();
+        let [uv0, uv1, uv2, uv3] = flip_uv_corners(region.uv_corners(), self.flip);
 
         let bl = center - half_size;
         let tr = center + half_size;
@@ -81,7 +84,7 @@ impl<'a> PainterContext<'a> {
             return
         };
 
-        let [uv0, uv1, uv2, uv3] = region.uv_corners();
+        let [uv0, uv1, uv2, uv3] = flip_uv_corners(region.uv_corners(), self.flip);
         self.quads.request(self.painter, &region.page.texture, self.blend, self.layer, [[
             Vertex::new(vertices[0], self.color, uv0),
             Vertex::new(vertices[1], self.color, uv1),
@@ -97,7 +100,7 @@ impl<'a> PainterContext<'a> {
             return
         };
 
-        let [uv0, uv1, uv2, uv3] = region.uv_corners();
+        let [uv0, uv1, uv2, uv3] = flip_uv_corners(region.uv_corners(), self.flip);
 
         let Some([cos, sin]) = (to - from).try_normalize().map(|v| v.to_array()) else { return };
         let bias = vec2(sin, -cos);
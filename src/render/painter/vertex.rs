@@ -1,4 +1,6 @@
 use crate::prelude::*;
+#[cfg(feature = "dev")]
+use crate::render::RenderFreeze;
 
 #[derive(Pod, Zeroable, Debug, Clone, Copy)]
 #[repr(C)]
@@ -136,10 +138,21 @@ fn extract_quads_and_painters(
     mut state: Local<Option<SystemState<(ResMut<PainterQuads>, Query<(RenderEntity, &mut Painter)>)>>>,
     mut new_render_painters: Local<Vec<(Entity, RenderPainter)>>,
 ) {
+    // While frozen, the main world's buffers are still drained so they don't pile up, but their
+    // contents are discarded and the render world keeps what it extracted last.
+    #[cfg(feature = "dev")]
+    let frozen = main_world.get_resource::<RenderFreeze>().is_some_and(|freeze| freeze.is_skipping());
+    #[cfg(not(feature = "dev"))]
+    let frozen = false;
+
     let (mut painter_quads, painters) = state.get_or_insert_with(|| SystemState::new(&mut main_world)).get_mut(&mut main_world);
     ComputeTaskPool::get().scope(|scope| {
         scope.spawn(async move {
             painter_quads.quads.clear(|slice| {
+                if frozen {
+                    return
+                }
+
                 let slice: &[u8] = cast_slice(&slice);
                 if painter_buffer.size() < slice.len() as BufferAddress {
                     **painter_buffer = device.create_buffer(&BufferDescriptor {
@@ -164,6 +177,7 @@ fn extract_quads_and_painters(
         scope.spawn(async move {
             for (render_entity, mut painter) in painters {
                 painter.requests.clear(|slice| match render_painters.get_mut(render_entity) {
+                    _ if frozen => {}
                     Ok(mut render_painter) => {
                         render_painter.requests.clear();
                         render_painter.requests.extend_from_slice(&slice);
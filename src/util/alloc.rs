@@ -0,0 +1,147 @@
+use std::{
+    alloc::{GlobalAlloc, Layout},
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+use crate::prelude::*;
+
+/// Global allocator wrapper counting allocations for [`AllocStats`], installed around MiMalloc in
+/// `dev` builds only.
+///
+/// Every (de)allocation costs a few extra relaxed atomic operations, which is measurable in
+/// allocation-heavy code but negligible otherwise. Counters are updated independently of each
+/// other, so a snapshot taken while other threads allocate may be slightly off.
+pub struct CountingAlloc<A> {
+    inner: A,
+    live: AtomicUsize,
+    peak: AtomicUsize,
+    allocs: AtomicUsize,
+    allocated: AtomicUsize,
+}
+
+impl<A> CountingAlloc<A> {
+    pub const fn new(inner: A) -> Self {
+        Self {
+            inner,
+            live: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            allocs: AtomicUsize::new(0),
+            allocated: AtomicUsize::new(0),
+        }
+    }
+
+    fn record_alloc(&self, size: usize) {
+        self.allocs.fetch_add(1, Relaxed);
+        self.allocated.fetch_add(size, Relaxed);
+        let live = self.live.fetch_add(size, Relaxed) + size;
+        self.peak.fetch_max(live, Relaxed);
+    }
+
+    fn record_dealloc(&self, size: usize) {
+        self.live.fetch_sub(size, Relaxed);
+    }
+
+    /// Returns the counters accumulated since the last call, and starts a new frame.
+    fn take_frame(&self) -> AllocStats {
+        let live = self.live.load(Relaxed);
+        AllocStats {
+            allocs: self.allocs.swap(0, Relaxed),
+            allocated: self.allocated.swap(0, Relaxed),
+            live,
+            peak: self.peak.swap(live, Relaxed),
+        }
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for CountingAlloc<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner.alloc(layout) };
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.inner.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { self.inner.dealloc(ptr, layout) };
+        self.record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { self.inner.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            // Counted as a fresh allocation, as it may well have moved.
+            self.record_dealloc(layout.size());
+            self.record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Allocation counters of the previous frame, in bytes where applicable.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AllocStats {
+    /// Number of allocations made.
+    pub allocs: usize,
+    /// Total size of the allocations made.
+    pub allocated: usize,
+    /// Memory in use at the end of the frame.
+    pub live: usize,
+    /// Highest memory in use at any point during the frame.
+    pub peak: usize,
+}
+
+/// Whether [`AllocStats`] are shown in the primary window's title, toggled with [`KeyCode::F7`].
+/// The title is used since there's no font to render an overlay with.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq, Deref, DerefMut)]
+pub struct AllocOverlay(pub bool);
+
+fn update_alloc_stats(mut stats: ResMut<AllocStats>) {
+    stats.set_if_neq(crate::ALLOC.take_frame());
+}
+
+fn update_alloc_overlay(
+    keys: Res<ButtonInput<KeyCode>>,
+    stats: Res<AllocStats>,
+    mut overlay: ResMut<AllocOverlay>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    mut title: Local<Option<String>>,
+) {
+    fn kib(bytes: usize) -> f32 {
+        bytes as f32 / 1024.
+    }
+
+    if keys.just_pressed(KeyCode::F7) {
+        **overlay = !**overlay;
+    }
+
+    let base = title.get_or_insert_with(|| window.title.clone());
+    match **overlay {
+        true => {
+            window.title = format!(
+                "{base} | {} allocs, {:.1} KiB this frame | {:.1} KiB live, {:.1} KiB peak",
+                stats.allocs,
+                kib(stats.allocated),
+                kib(stats.live),
+                kib(stats.peak),
+            );
+        }
+        false if overlay.is_changed() => window.title = base.clone(),
+        false => {}
+    }
+}
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<AllocStats>()
+        .init_resource::<AllocOverlay>()
+        .add_systems(First, update_alloc_stats)
+        .add_systems(Last, update_alloc_overlay);
+}
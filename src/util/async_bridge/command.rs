@@ -49,6 +49,9 @@ impl AsyncCommands<'_> {
         Ok(entity_commands)
     }
 
+    /// Reserves `count` entities in one go. Each call gets its own reply channel, so the returned
+    /// set never mixes with other tasks' requests, and it stays in the order the main world
+    /// reserved them in, i.e. it can be zipped with whatever the entities were requested for.
     pub async fn spawn_many(&self, count: u32) -> Result<SmallVec<[Entity; 1]>> {
         self.ctx.entity.send(count).await
     }
@@ -64,6 +67,18 @@ impl AsyncCommands<'_> {
         self.ctx.command.send(mem::take(&mut self.queue)).await.map_err(|_| "Channel closed.")?;
         Ok(())
     }
+
+    /// Submits the pending commands followed by each of `queues` in order, as a single unit that
+    /// the main world applies in one go with only one completion signal.
+    pub async fn submit_batch(&mut self, queues: Vec<CommandQueue>) -> Result {
+        let mut batch = mem::take(&mut *self.queue);
+        for mut queue in queues {
+            batch.append(&mut queue);
+        }
+
+        self.ctx.command.send(batch).await.map_err(|_| "Channel closed.")?;
+        Ok(())
+    }
 }
 
 pub struct AsyncEntityCommands<'a> {
@@ -164,3 +179,102 @@ impl Default for Queue<'_> {
         Self::Owned(default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use bevy::ecs::system::RunSystemOnce;
+    use futures_lite::future::block_on;
+
+    use super::*;
+    use crate::util::async_bridge::{AsyncBridge, execute_async_commands, execute_async_entities};
+
+    #[test]
+    fn spawn_many_from_concurrent_tasks() {
+        const TASKS: u32 = 8;
+        const CALLS: u32 = 64;
+
+        let mut world = World::new();
+        // Small capacity, so tasks keep waiting on each other and their requests interleave.
+        let bridge = AsyncBridge::bounded(4);
+        world.insert_resource(bridge.clone());
+
+        let tasks = (0..TASKS)
+            .map(|task| {
+                let ctx = bridge.ctx();
+                thread::spawn(move || {
+                    let commands = ctx.commands();
+                    (0..CALLS)
+                        .map(|call| {
+                            let count = 1 + (task + call) % 5;
+                            let entities = block_on(commands.spawn_many(count)).unwrap();
+                            assert_eq!(entities.len(), count as usize);
+                            entities
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        while !tasks.iter().all(|task| task.is_finished()) {
+            world.run_system_once(execute_async_entities).unwrap();
+            thread::yield_now();
+        }
+
+        let mut seen = HashSet::new();
+        for set in tasks.into_iter().flat_map(|task| task.join().unwrap()) {
+            // Fresh entities are reserved with consecutive indices, so anything else means the set
+            // got reordered or mixed with another request's.
+            assert!(set.windows(2).all(|pair| pair[1].index() == pair[0].index() + 1), "{set:?}");
+            assert!(set.iter().all(|&e| seen.insert(e)), "{set:?}");
+        }
+
+        assert_eq!(
+            seen.len() as u32,
+            (0..TASKS).flat_map(|task| (0..CALLS).map(move |call| 1 + (task + call) % 5)).sum()
+        );
+    }
+
+    #[test]
+    fn submit_batch_in_order() {
+        #[derive(Resource, Default)]
+        struct Order(Vec<u32>);
+
+        fn push(n: u32) -> impl FnOnce(&mut World) + Send + 'static {
+            move |world: &mut World| world.resource_mut::<Order>().0.push(n)
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Order>();
+        let bridge = AsyncBridge::bounded(4);
+        world.insert_resource(bridge.clone());
+
+        let ctx = bridge.ctx();
+        let task = thread::spawn(move || {
+            let mut commands = ctx.commands();
+            commands.queue(push(0));
+
+            let queues = (1..=3)
+                .map(|n| {
+                    let mut queue = CommandQueue::default();
+                    queue.push(push(n));
+                    queue
+                })
+                .collect();
+
+            block_on(commands.submit_batch(queues)).unwrap();
+        });
+
+        while bridge.command_channel.1.is_empty() {
+            thread::yield_now();
+        }
+
+        // Everything arrives as one queue, so there's only one completion signal to wait for.
+        assert_eq!(bridge.command_channel.1.len(), 1);
+        world.run_system_once(execute_async_commands).unwrap();
+        task.join().unwrap();
+
+        assert_eq!(world.resource::<Order>().0, [0, 1, 2, 3]);
+    }
+}
@@ -1,22 +1,57 @@
 mod command;
+mod timer;
+
 pub use command::*;
+pub use timer::*;
 
 use crate::prelude::*;
 
 type Channel<T> = (async_channel::Sender<T>, async_channel::Receiver<T>);
 
+/// Channels between async tasks and the main world. Bounded by default, so a runaway task waits for
+/// the main world to catch up instead of piling up requests; insert a different bridge before
+/// adding the plugin to change that.
 #[derive(Resource, Clone)]
 pub struct AsyncBridge {
     command_channel: Channel<(CommandQueue, async_channel::Sender<()>)>,
     asset_channel: Channel<(UntypedHandle, async_channel::Sender<Box<dyn Reflect>>)>,
+    loaded_asset_channel: Channel<(UntypedHandle, async_channel::Sender<Box<dyn Reflect>>)>,
     entity_channel: Channel<(u32, async_channel::Sender<SmallVec<[Entity; 1]>>)>,
 }
 
 impl AsyncBridge {
+    pub const DEFAULT_CAPACITY: usize = 128;
+
+    /// Each channel holds at most `capacity` pending requests, with senders waiting for a free
+    /// slot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is `0`, since nothing could ever be sent.
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(capacity > 0, "Async bridge capacity must be at least 1");
+        Self {
+            command_channel: async_channel::bounded(capacity),
+            asset_channel: async_channel::bounded(capacity),
+            loaded_asset_channel: async_channel::bounded(capacity),
+            entity_channel: async_channel::bounded(capacity),
+        }
+    }
+
+    pub fn unbounded() -> Self {
+        Self {
+            command_channel: async_channel::unbounded(),
+            asset_channel: async_channel::unbounded(),
+            loaded_asset_channel: async_channel::unbounded(),
+            entity_channel: async_channel::unbounded(),
+        }
+    }
+
     pub fn ctx(&self) -> AsyncContext {
         AsyncContext {
             command: AsyncMessager(self.command_channel.0.clone()),
             asset: AsyncMessager(self.asset_channel.0.clone()),
+            loaded_asset: AsyncMessager(self.loaded_asset_channel.0.clone()),
             entity: AsyncMessager(self.entity_channel.0.clone()),
         }
     }
@@ -24,11 +59,7 @@ impl AsyncBridge {
 
 impl Default for AsyncBridge {
     fn default() -> Self {
-        Self {
-            command_channel: async_channel::unbounded(),
-            asset_channel: async_channel::unbounded(),
-            entity_channel: async_channel::unbounded(),
-        }
+        Self::bounded(Self::DEFAULT_CAPACITY)
     }
 }
 
@@ -36,9 +67,19 @@ impl Default for AsyncBridge {
 pub struct AsyncContext {
     pub command: AsyncMessager<CommandQueue, ()>,
     pub asset: AsyncMessager<UntypedHandle, Box<dyn Reflect>>,
+    /// Replies with a clone of the asset once it's done loading, leaving the original in place.
+    pub loaded_asset: AsyncMessager<UntypedHandle, Box<dyn Reflect>>,
     pub entity: AsyncMessager<u32, SmallVec<[Entity; 1]>>,
 }
 
+impl AsyncContext {
+    /// Waits for the asset behind `handle` to finish loading, then returns a clone of it. Fails if
+    /// the asset fails to load or can't be cloned through reflection.
+    pub async fn get_when_loaded<A: Asset>(&self, handle: &Handle<A>) -> Result<A> {
+        self.loaded_asset.send_typed(handle.clone().untyped()).await
+    }
+}
+
 pub struct AsyncMessager<In, Out>(async_channel::Sender<(In, async_channel::Sender<Out>)>);
 impl<In, Out> AsyncMessager<In, Out> {
     pub async fn send(&self, input: In) -> Result<Out> {
@@ -46,6 +87,19 @@ impl<In, Out> AsyncMessager<In, Out> {
         self.0.send((input, tx)).await.map_err(|_| "Channel closed.")?;
         Ok(rx.recv().await?)
     }
+
+    /// Same as [`send()`](Self::send), but fails if the reply doesn't arrive within `timeout`, e.g.
+    /// when the main world stops running the bridge's executors while shutting down.
+    ///
+    /// Dropping the future before it completes is fine; the main world discards replies that nobody
+    /// is waiting for.
+    pub async fn send_timeout(&self, input: In, timeout: Duration) -> Result<Out> {
+        futures_lite::future::or(self.send(input), async move {
+            Sleep::new(timeout).await;
+            Err(format!("No reply received within {}ms", timeout.as_millis()).into())
+        })
+        .await
+    }
 }
 
 impl<In> AsyncMessager<In, Box<dyn Reflect>> {
@@ -92,14 +146,211 @@ fn execute_async_assets(mut commands: Commands, bridge: Res<AsyncBridge>, regist
     Ok(())
 }
 
+fn execute_async_loaded_assets(
+    mut commands: Commands,
+    bridge: Res<AsyncBridge>,
+    server: Res<AssetServer>,
+    registry: Res<AppTypeRegistry>,
+    mut pending: Local<Vec<(UntypedHandle, async_channel::Sender<Box<dyn Reflect>>)>>,
+) {
+    let registry = registry.clone();
+    let requests = mem::take(&mut *pending)
+        .into_iter()
+        .chain(iter::from_fn(|| bridge.loaded_asset_channel.1.try_recv().ok()));
+
+    // Dropping the sender makes the receiving end fail, so each request fails on its own without
+    // losing the others.
+    for (handle, sender) in requests {
+        let load_state = server.load_state(handle.id());
+        if sender.is_closed() || load_state.is_failed() {
+            continue
+        } else if load_state.is_loading() {
+            pending.push((handle, sender));
+            continue
+        }
+
+        let Some(asset_fns) = registry.read().get_type_data::<ReflectAsset>(handle.type_id()).cloned() else {
+            error!("Missing `ReflectAsset` for {handle:?}");
+            continue
+        };
+
+        commands.queue(move |world: &mut World| -> Result {
+            let asset = asset_fns.get(world, handle.id()).ok_or("Missing asset.")?.reflect_clone()?;
+            _ = sender.try_send(asset);
+
+            Ok(())
+        });
+    }
+}
+
 fn execute_async_entities(entities: &Entities, bridge: Res<AsyncBridge>) {
     while let Ok((len, sender)) = bridge.entity_channel.1.try_recv() {
+        // Collected in reservation order, which `AsyncCommands::spawn_many()` callers rely on.
         let entities = entities.reserve_entities(len).collect();
         _ = sender.try_send(entities);
     }
 }
 
 pub fn plugin(app: &mut App) {
-    app.init_resource::<AsyncBridge>()
-        .add_systems(PreUpdate, (execute_async_commands, execute_async_assets, execute_async_entities));
+    app.init_resource::<AsyncBridge>().add_systems(
+        PreUpdate,
+        (
+            execute_async_commands,
+            execute_async_assets,
+            execute_async_loaded_assets,
+            execute_async_entities,
+        ),
+    );
+
+    #[cfg(target_family = "wasm")]
+    app.add_systems(PreUpdate, timer::wake_expired_timers);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+
+    use bevy::{
+        asset::io::{
+            AssetSource,
+            memory::{Dir, MemoryAssetReader},
+        },
+        ecs::system::RunSystemOnce,
+        platform::time::Instant,
+    };
+    use futures_lite::future::{block_on, poll_once};
+
+    use super::*;
+
+    #[test]
+    fn send_timeout_fails_without_reply() {
+        let (tx, rx) = async_channel::unbounded::<(u32, async_channel::Sender<u32>)>();
+        let messager = AsyncMessager(tx);
+
+        // The main world dropping the reply channel fails right away instead of at the deadline.
+        let dropper = std::thread::spawn({
+            let rx = rx.clone();
+            move || drop(rx.recv_blocking())
+        });
+
+        let start = Instant::now();
+        assert!(block_on(messager.send_timeout(0, Duration::from_secs(30))).is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+        dropper.join().unwrap();
+
+        // A request that's received but never answered fails once the timeout runs out.
+        let start = Instant::now();
+        assert!(block_on(messager.send_timeout(1, Duration::from_millis(50))).is_err());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(rx.try_recv().map(|(input, ..)| input), Ok(1));
+    }
+
+    #[test]
+    fn send_timeout_returns_reply() {
+        let (tx, rx) = async_channel::unbounded::<(u32, async_channel::Sender<u32>)>();
+        let messager = AsyncMessager(tx);
+
+        let replier = std::thread::spawn(move || {
+            let (input, reply) = rx.recv_blocking().unwrap();
+            reply.send_blocking(input * 2).unwrap();
+        });
+
+        assert_eq!(block_on(messager.send_timeout(21, Duration::from_secs(30))).ok(), Some(42));
+        replier.join().unwrap();
+    }
+
+    #[test]
+    fn bounded_senders_wait_for_a_free_slot() {
+        let mut world = World::new();
+        let bridge = AsyncBridge::bounded(1);
+        world.insert_resource(bridge.clone());
+
+        let ctx = bridge.ctx();
+        let mut first = pin!(ctx.entity.send(1));
+        let mut second = pin!(ctx.entity.send(2));
+
+        // The first request takes the only slot, so the second can't even be sent yet.
+        assert!(block_on(poll_once(&mut first)).is_none());
+        assert!(block_on(poll_once(&mut second)).is_none());
+        assert_eq!(bridge.entity_channel.1.len(), 1);
+
+        // Draining the channel answers the first request and frees the slot for the second.
+        world.run_system_once(execute_async_entities).unwrap();
+        assert!(block_on(poll_once(&mut second)).is_none());
+        assert_eq!(bridge.entity_channel.1.len(), 1);
+        assert_eq!(block_on(first).unwrap().len(), 1);
+
+        world.run_system_once(execute_async_entities).unwrap();
+        assert_eq!(block_on(second).unwrap().len(), 2);
+    }
+
+    #[test]
+    #[should_panic = "Async bridge capacity must be at least 1"]
+    fn bounded_rejects_zero_capacity() {
+        AsyncBridge::bounded(0);
+    }
+
+    #[test]
+    fn get_when_loaded() {
+        #[derive(Asset, Reflect, Debug, Clone)]
+        #[reflect(Asset, Clone)]
+        struct Text(String);
+
+        #[derive(Asset, TypePath, Debug)]
+        struct Unreflected;
+
+        struct TextLoader;
+        impl AssetLoader for TextLoader {
+            type Asset = Text;
+            type Settings = ();
+            type Error = BevyError;
+
+            async fn load(&self, reader: &mut dyn Reader, _: &Self::Settings, _: &mut LoadContext<'_>) -> Result<Self::Asset, Self::Error> {
+                let mut text = String::new();
+                reader.read_to_string(&mut text).await?;
+                Ok(Text(text))
+            }
+
+            fn extensions(&self) -> &[&str] {
+                &["txt"]
+            }
+        }
+
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("hello.txt"), "Hello!");
+
+        let mut app = App::new();
+        app.register_asset_source(
+            "test",
+            AssetSource::build().with_reader(move || Box::new(MemoryAssetReader { root: dir.clone() })),
+        )
+        .add_plugins((TaskPoolPlugin::default(), AssetPlugin::default(), plugin))
+        .init_asset::<Text>()
+        .init_asset::<Unreflected>()
+        .register_asset_reflect::<Text>()
+        .register_asset_loader(TextLoader);
+
+        let world = app.world_mut();
+        let text = world.resource::<AssetServer>().load::<Text>("test://hello.txt");
+        let unreflected = world.resource_mut::<Assets<Unreflected>>().add(Unreflected);
+
+        // Both are requested before the text is loaded, and the unreflected asset failing must not
+        // affect the other request.
+        let ctx = world.resource::<AsyncBridge>().ctx();
+        let task = AsyncComputeTaskPool::get().spawn({
+            let text = text.clone();
+            async move { futures_lite::future::zip(ctx.get_when_loaded(&text), ctx.get_when_loaded(&unreflected)).await }
+        });
+
+        while !task.is_finished() {
+            app.update();
+        }
+
+        let (loaded, failed) = block_on(task);
+        assert_eq!(loaded.unwrap().0, "Hello!");
+        assert!(failed.is_err());
+
+        // The asset is cloned, not taken.
+        assert!(app.world().resource::<Assets<Text>>().contains(&text));
+    }
 }
@@ -0,0 +1,119 @@
+use std::{
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll, Waker},
+};
+
+use bevy::platform::time::Instant;
+
+use crate::prelude::*;
+
+/// Wakers waiting for their deadline, shared by every [`Sleep`] so timeouts don't need a thread
+/// each. Natively, a single thread started on first use wakes them; on the web, where there are no
+/// threads to block, [`wake_expired_timers()`] does so every frame instead.
+static TIMERS: Timers = Timers {
+    pending: Mutex::new(Vec::new()),
+    #[cfg(not(target_family = "wasm"))]
+    changed: std::sync::Condvar::new(),
+};
+
+struct Timers {
+    pending: Mutex<Vec<(Instant, Waker)>>,
+    #[cfg(not(target_family = "wasm"))]
+    changed: std::sync::Condvar,
+}
+
+impl Timers {
+    fn register(&self, deadline: Instant, waker: &Waker) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        if pending
+            .iter()
+            .any(|(other, other_waker)| *other == deadline && other_waker.will_wake(waker))
+        {
+            return
+        }
+
+        pending.push((deadline, waker.clone()));
+        drop(pending);
+
+        #[cfg(not(target_family = "wasm"))]
+        {
+            static STARTED: std::sync::Once = std::sync::Once::new();
+            STARTED.call_once(|| {
+                std::thread::Builder::new()
+                    .name("Async bridge timer".into())
+                    .spawn(|| TIMERS.run())
+                    .expect("Couldn't spawn the async bridge timer thread");
+            });
+
+            self.changed.notify_one();
+        }
+    }
+
+    /// Removes and returns the wakers of expired deadlines, along with the nearest remaining one.
+    fn take_expired(pending: &mut Vec<(Instant, Waker)>, now: Instant) -> (Vec<Waker>, Option<Instant>) {
+        let mut expired = Vec::new();
+        pending.retain(|(deadline, waker)| match *deadline <= now {
+            true => {
+                expired.push(waker.clone());
+                false
+            }
+            false => true,
+        });
+
+        (expired, pending.iter().map(|&(deadline, ..)| deadline).min())
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    fn run(&self) -> ! {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            let now = Instant::now();
+            let (expired, next) = Self::take_expired(&mut pending, now);
+            if !expired.is_empty() {
+                // Don't hold the lock while waking, in case a waker polls its task right away.
+                drop(pending);
+                expired.into_iter().for_each(Waker::wake);
+                pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+                continue
+            }
+
+            pending = match next {
+                Some(next) => self.changed.wait_timeout(pending, next - now).unwrap_or_else(|e| e.into_inner()).0,
+                None => self.changed.wait(pending).unwrap_or_else(|e| e.into_inner()),
+            };
+        }
+    }
+}
+
+/// Resolves once `deadline` has passed.
+pub struct Sleep {
+    deadline: Instant,
+}
+
+impl Sleep {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + duration,
+        }
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(())
+        }
+
+        TIMERS.register(self.deadline, cx.waker());
+        Poll::Pending
+    }
+}
+
+#[cfg(target_family = "wasm")]
+pub(super) fn wake_expired_timers() {
+    let (expired, ..) = Timers::take_expired(&mut TIMERS.pending.lock().unwrap_or_else(|e| e.into_inner()), Instant::now());
+    expired.into_iter().for_each(Waker::wake);
+}
@@ -1,4 +1,9 @@
+#[cfg(all(feature = "dev", not(target_family = "wasm")))]
+mod alloc;
 mod iter;
+#[cfg(all(feature = "dev", not(target_family = "wasm")))]
+pub use alloc::*;
+
 pub use iter::*;
 
 pub mod async_bridge;
@@ -13,5 +18,10 @@ pub const fn replace_with<T>(src: T, dst: &mut T) -> T {
 }
 
 pub fn plugin(app: &mut App) {
-    app.add_plugins((async_bridge::plugin, ecs::plugin));
+    app.add_plugins((
+        async_bridge::plugin,
+        ecs::plugin,
+        #[cfg(all(feature = "dev", not(target_family = "wasm")))]
+        alloc::plugin,
+    ));
 }
@@ -1,10 +1,12 @@
+use bevy::reflect::ReflectRef;
+
 use crate::{
     GameState, ProgressFor, ProgressSystems,
     math::Transform2d,
     prelude::*,
     render::MainCamera,
     util::{IteratorExt, async_bridge::AsyncBridge},
-    world::{LevelCollectionRef, Tile, Tilemap, TilemapParallax, WorldEnum},
+    world::{DEFAULT_WORLD, LevelCollectionRef, LevelCollections, LevelId, Tile, Tilemap, TilemapParallax, WorldEnum},
 };
 
 #[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
@@ -33,22 +35,99 @@ pub enum LoadLevel {
 }
 
 impl LoadLevel {
+    /// Queues a level to be loaded. The identifier may be qualified with its world's name, as
+    /// described in [`LevelId`].
     pub fn load(&mut self, level_identifier: impl Into<String>) {
         *self = Self::Pending(level_identifier.into());
     }
 }
 
+/// Size of the currently loaded level in pixels, spanning from the origin.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Deref, DerefMut)]
+pub struct LevelBounds(pub Vec2);
+
+/// Marks an entity spawned from a level, to be despawned when another level is loaded.
+///
+/// Level entities are despawned in reverse spawn order (the value in this component), so entities
+/// spawned later (which may depend on earlier ones) are always despawned first. Children are
+/// despawned along with their parents as usual, and components that own non-child entities (e.g.
+/// [`Tilemap`] tiles or [`Hair`](crate::entities::Hair) strands) despawn them in their hooks.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[component(immutable)]
+pub struct LevelUnload(pub u32);
+
+/// The level that is currently loaded, or being loaded.
+#[derive(Resource, Debug, Clone)]
+pub struct CurrentLevel {
+    pub world: String,
+    pub identifier: String,
+    /// `true` if the level hasn't been loaded before in this session.
+    pub first_visit: bool,
+}
+
+/// Qualified identifiers of every level that has been loaded in this session.
+#[derive(Resource, Debug, Default)]
+pub struct VisitedLevels(HashSet<LevelId>);
+impl VisitedLevels {
+    /// Qualifies `level_identifier` the same way [`LoadLevel`] does, so unqualified identifiers of
+    /// levels outside [`DEFAULT_WORLD`] match too. Pass [`None`] when no worlds are loaded.
+    pub fn contains(&self, level_identifier: &str, collections: Option<&LevelCollections>) -> bool {
+        let id = LevelId::from(level_identifier);
+        self.0.contains(&match collections {
+            Some(collections) => collections.qualify(id),
+            None => id.qualified(),
+        })
+    }
+}
+
+/// Run condition for level spawners that should only set up on the first visit to the current
+/// level, e.g. cutscenes.
+pub fn if_first_visit(level: Option<Res<CurrentLevel>>) -> bool {
+    level.is_some_and(|level| level.first_visit)
+}
+
+/// Run condition for level spawners that should only set up when revisiting the current level.
+pub fn if_revisit(level: Option<Res<CurrentLevel>>) -> bool {
+    level.is_some_and(|level| !level.first_visit)
+}
+
 #[derive(Debug)]
 pub struct EntityFields {
     pub map: HashMap<String, EntityField>,
 }
 
+impl EntityFields {
+    pub fn get(&self, name: &str) -> Result<&EntityField> {
+        Ok(self.map.get(name).ok_or_else(|| format!("Missing field `{name}`"))?)
+    }
+
+    pub fn color(&self, name: &str) -> Result<Color> {
+        match self.get(name)? {
+            &EntityField::Color(color) => Ok(color),
+            other => Err(format!("Field `{name}` is {other:?}, expected a color"))?,
+        }
+    }
+
+    /// Returns the variant name of an enum field, e.g. for matching on behaviors without going
+    /// through the enum's type.
+    pub fn enum_variant(&self, name: &str) -> Result<&str> {
+        match self.get(name)? {
+            EntityField::Enum(value) => match value.reflect_ref() {
+                ReflectRef::Enum(value) => Ok(value.variant_name()),
+                _ => Err(format!("Field `{name}` isn't a reflected enum"))?,
+            },
+            other => Err(format!("Field `{name}` is {other:?}, expected an enum"))?,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum EntityField {
     Int(i64),
     Float(f64),
     String(String),
     Path(PathBuf),
+    Color(Color),
     Enum(Arc<dyn WorldEnum>),
     GridPoint(UVec2),
     Tileset { id: u32, rect: URect },
@@ -78,6 +157,75 @@ impl MessageReaderEntityExt for MessageReader<'_, '_, EntityCreate> {
 pub enum LayerCreate {
     Entities { identifier: String, entities: Vec<Entity> },
     Tiles { entity: Entity, kind: TileLayerKind },
+    IntGrid { identifier: String, entity: Entity },
+}
+
+/// Values of an LDtk IntGrid layer, with `(0, 0)` at the bottom-left like [`Tilemap`]s. A value of
+/// `0` means an empty cell.
+#[derive(Component, Debug, Clone)]
+#[component(immutable)]
+pub struct IntGrid {
+    pub grid_size: f32,
+    pub dimension: UVec2,
+    pub cells: Vec<u32>,
+}
+
+impl IntGrid {
+    pub fn get(&self, pos: UVec2) -> Option<u32> {
+        (pos.x < self.dimension.x)
+            .then(|| self.cells.get(pos.y as usize * self.dimension.x as usize + pos.x as usize).copied())
+            .flatten()
+    }
+
+    pub fn iter_cells(&self) -> impl Iterator<Item = (UVec2, u32)> {
+        let width = self.dimension.x.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, &value)| (uvec2(i as u32 % width, i as u32 / width), value))
+    }
+}
+
+/// How cells of a registered IntGrid value collide; see [`AppIntCellExt`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntCellCollision {
+    /// A static rigid body.
+    Solid,
+    /// A static sensor, e.g. for triggers.
+    Sensor,
+}
+
+/// IntGrid values that create colliders, keyed by layer identifier and cell value.
+#[derive(Resource, Debug, Default)]
+pub struct IntCellColliders(HashMap<(String, u32), IntCellCollision>);
+
+impl IntCellColliders {
+    /// Groups the cells of `int_grid` that create colliders by their value and collision.
+    fn cells(&self, identifier: &str, int_grid: &IntGrid) -> HashMap<(u32, IntCellCollision), Vec<IVec2>> {
+        let mut cells = HashMap::<_, Vec<_>>::new();
+        for (pos, value) in int_grid.iter_cells() {
+            if let Some(&collision) = self.0.get(&(identifier.to_string(), value)) {
+                cells.entry((value, collision)).or_default().push(pos.as_ivec2());
+            }
+        }
+        cells
+    }
+}
+
+pub trait AppIntCellExt {
+    /// Makes every cell with `value` in IntGrid layers named `layer` create a collider, merged per
+    /// layer and value like tile colliders.
+    fn register_int_cell_collision(&mut self, layer: impl Into<String>, value: u32, collision: IntCellCollision) -> &mut Self;
+}
+
+impl AppIntCellExt for App {
+    fn register_int_cell_collision(&mut self, layer: impl Into<String>, value: u32, collision: IntCellCollision) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<IntCellColliders>()
+            .0
+            .insert((layer.into(), value), collision);
+        self
+    }
 }
 
 /// Tile layer identifier, defined in reverse order as specified in the LDtkl file.
@@ -120,36 +268,66 @@ impl MessageReaderLayerExt for MessageReader<'_, '_, LayerCreate> {
 
 #[derive(Resource)]
 enum LoadLevelProgress {
-    Pending(String),
-    Running(Duration, Task<Result<LoadLevelOutput>>),
+    Pending(LevelId),
+    Running(LevelId, Duration, Task<Result<LoadLevelOutput>>),
     Done,
 }
 
-fn load_level_transition(mut commands: Commands, mut load_level: ResMut<LoadLevel>, mut state: ResMut<NextState<GameState>>) {
+fn load_level_transition(
+    mut commands: Commands,
+    mut load_level: ResMut<LoadLevel>,
+    visited: Res<VisitedLevels>,
+    mut state: ResMut<NextState<GameState>>,
+    collections: Option<Res<LevelCollections>>,
+    unloads: Query<(Entity, &LevelUnload)>,
+) {
     let LoadLevel::Pending(level_identifier) = mem::take(&mut *load_level) else { return };
-    commands.insert_resource(LoadLevelProgress::Pending(level_identifier));
+
+    let mut unloads = unloads.iter().collect::<Vec<_>>();
+    unloads.sort_unstable_by(|(.., a), (.., b)| b.cmp(a));
+    for (entity, ..) in unloads {
+        // Entities may already be despawned by a despawned parent.
+        commands.entity(entity).try_despawn();
+    }
+
+    let id = LevelId::from(level_identifier);
+    let id = match collections {
+        Some(collections) => collections.qualify(id),
+        None => id.qualified(),
+    };
+
+    commands.insert_resource(CurrentLevel {
+        world: id.world().into(),
+        identifier: id.level.clone(),
+        // Only marked as visited once loading succeeds, in `load_level()`.
+        first_visit: !visited.0.contains(&id),
+    });
+    commands.insert_resource(LoadLevelProgress::Pending(id));
     state.set(GameState::LevelLoading);
 }
 
 fn load_level(
+    mut commands: Commands,
     progress: ProgressFor<GameState>,
     time: Res<Time>,
     server: Res<AssetServer>,
     bridge: Res<AsyncBridge>,
-    collection: Res<LevelCollectionRef>,
+    collections: Res<LevelCollections>,
     mut load_level: ResMut<LoadLevelProgress>,
+    mut visited: ResMut<VisitedLevels>,
     mut entity_creation_writer: MessageWriter<EntityCreate>,
     mut layer_creation_writer: MessageWriter<LayerCreate>,
     mut camera: Single<&mut Camera, With<MainCamera>>,
 ) -> Result {
-    let LoadLevelProgress::Running(started, task) = (match &mut *load_level {
-        LoadLevelProgress::Pending(level_identifier) => {
-            info!("Begin level loading of {level_identifier}...");
+    let LoadLevelProgress::Running(id, started, task) = (match &mut *load_level {
+        LoadLevelProgress::Pending(id) => {
+            info!("Begin level loading of {id}...");
 
-            let level_identifier = mem::take(level_identifier);
+            let collection = collections.resolve(id)?;
             *load_level = LoadLevelProgress::Running(
+                id.clone(),
                 time.elapsed(),
-                AsyncComputeTaskPool::get().spawn(load_level_task(level_identifier, &server, &collection, &bridge)),
+                AsyncComputeTaskPool::get().spawn(load_level_task(id.level.clone(), &server, collection, &bridge)),
             );
             &mut *load_level
         }
@@ -166,6 +344,8 @@ fn load_level(
             entity_creation_writer.write_batch(output.entity_creation);
             layer_creation_writer.write_batch(output.layer_creation);
             camera.clear_color = ClearColorConfig::Custom(output.clear_color.into());
+            commands.insert_resource(LevelBounds(output.bounds));
+            visited.0.insert(id.clone());
 
             *load_level = LoadLevelProgress::Done;
             progress.update(true);
@@ -187,6 +367,7 @@ struct LoadLevelOutput {
     entity_creation: Vec<EntityCreate>,
     layer_creation: Vec<LayerCreate>,
     clear_color: Srgba,
+    bounds: Vec2,
 }
 
 fn load_level_task(
@@ -199,6 +380,8 @@ fn load_level_task(
     #[expect(non_snake_case, reason = "LDtk naming scheme")]
     struct Repr {
         __bgColor: String,
+        pxWid: u32,
+        pxHei: u32,
         layerInstances: Vec<LayerInstanceRepr>,
     }
 
@@ -225,12 +408,16 @@ fn load_level_task(
             __tilesetDefUid: u32,
             gridTiles: Vec<TileInstanceRepr>,
         },
+        IntGrid {
+            intGridCsv: Vec<u32>,
+        },
     }
 
     #[derive(Deserialize)]
     #[expect(non_snake_case, reason = "LDtk naming scheme")]
     struct EntityInstanceRepr {
         __identifier: String,
+        iid: String,
         __grid: [u32; 2],
         px: [u32; 2],
         __pivot: [f32; 2],
@@ -250,6 +437,8 @@ fn load_level_task(
     struct TileInstanceRepr {
         px: [u32; 2],
         t: u32,
+        /// Bit 0 flips horizontally, bit 1 flips vertically.
+        f: u8,
     }
 
     let server = server.clone();
@@ -269,9 +458,12 @@ fn load_level_task(
 
         let repr = serde_json::from_slice::<Repr>(&bytes)?;
         output.clear_color = Srgba::hex(repr.__bgColor)?;
+        output.bounds = uvec2(repr.pxWid, repr.pxHei).as_vec2();
 
         let mut commands = ctx.commands();
+        let mut spawn_order = 0;
         let mut used_names = HashSet::new();
+        let mut used_iids = HashSet::new();
         for (i, layer) in repr.layerInstances.into_iter().rev().enumerate() {
             if !used_names.insert(layer.__identifier.clone()) {
                 Err(format!("Duplicate layer {}", layer.__identifier))?
@@ -291,6 +483,16 @@ fn load_level_task(
                     });
 
                     for (instance, entity) in entityInstances.into_iter().zip(entities) {
+                        if !used_iids.insert(instance.iid.clone()) {
+                            Err(format!(
+                                "Duplicate entity IID {} (`{}` in layer {}) in level `{level_identifier}`",
+                                instance.iid, instance.__identifier, layer.__identifier
+                            ))?
+                        }
+
+                        commands.entity(entity).insert(LevelUnload(spawn_order));
+                        spawn_order += 1;
+
                         let size = uvec2(instance.width, instance.height).as_vec2();
                         let bounds_start = uvec2(instance.px[0], layer.__cHei * layer.__gridSize - instance.px[1]).as_vec2()
                             - vec2(instance.__pivot[0], 1. - instance.__pivot[1]) * size;
@@ -305,6 +507,7 @@ fn load_level_task(
                                         "Float" => field.__value.as_f64().map(EntityField::Float),
                                         "String" => field.__value.as_str().map(|s| EntityField::String(s.into())),
                                         "FilePath" => field.__value.as_str().map(|s| EntityField::Path(s.into())),
+                                        "Color" => field.__value.as_str().map(Srgba::hex).transpose()?.map(|c| EntityField::Color(c.into())),
                                         // TODO GridPoint, Tileset, Entity
                                         other => {
                                             if let Some(enum_name) = other.strip_prefix("LocalEnum.") {
@@ -332,6 +535,36 @@ fn load_level_task(
                         });
                     }
                 }
+                LayerDataRepr::IntGrid { intGridCsv } => {
+                    if intGridCsv.len() != layer.__cWid as usize * layer.__cHei as usize {
+                        Err(format!("IntGrid layer {} doesn't match its dimension", layer.__identifier))?
+                    }
+
+                    // LDtk rows go top-to-bottom; flip them so `(0, 0)` is the bottom-left like tiles.
+                    let cells = intGridCsv.chunks_exact(layer.__cWid.max(1) as usize).rev().flatten().copied().collect();
+
+                    let entity = commands
+                        .spawn_many(1)
+                        .await?
+                        .into_iter()
+                        .next()
+                        .expect("Exactly one entity was requested");
+                    commands.entity(entity).insert((
+                        LevelUnload(spawn_order),
+                        IntGrid {
+                            grid_size: layer.__gridSize as f32,
+                            dimension: uvec2(layer.__cWid, layer.__cHei),
+                            cells,
+                        },
+                        Transform2d::default(),
+                    ));
+                    spawn_order += 1;
+
+                    output.layer_creation.push(LayerCreate::IntGrid {
+                        identifier: layer.__identifier,
+                        entity,
+                    });
+                }
                 LayerDataRepr::Tiles { __tilesetDefUid, gridTiles } => {
                     let tileset = collection
                         .tilesets
@@ -340,7 +573,9 @@ fn load_level_task(
                     let mut entities = commands.spawn_many(gridTiles.len() as u32 + 1).await?.into_iter();
 
                     let tilemap_entity = entities.next().expect("Non-zero integer was provided; the entity must exist");
+                    // Tiles don't need `LevelUnload`, as they're despawned along with their tilemap.
                     commands.entity(tilemap_entity).insert((
+                        LevelUnload(spawn_order),
                         Tilemap::new(layer.__gridSize as f32, uvec2(layer.__cWid, layer.__cHei)),
                         TilemapProperties {
                             tiles: tileset.properties.iter().try_map_into_default(|(key, value)| {
@@ -353,6 +588,7 @@ fn load_level_task(
                             })?,
                         },
                     ));
+                    spawn_order += 1;
 
                     let kind = match layer.__identifier.as_str() {
                         "tiles_back" => TileLayerKind::Back,
@@ -377,7 +613,8 @@ fn load_level_task(
                                     .tiles
                                     .get(&tileset_pos)
                                     .ok_or_else(|| format!("No tileset tile defined at ({tileset_pos})"))?,
-                            ),
+                            )
+                            .with_flip(BVec2::new(tile.f & 1 != 0, tile.f & 2 != 0)),
                             TileId(tile.t),
                         ));
                     }
@@ -443,19 +680,166 @@ fn create_tile_collider(
     }
 }
 
+/// Scans every level of every loaded world for entity instances sharing an IID, or entity reference
+/// fields pointing at IIDs that don't exist, and warns about each of them.
+#[cfg(feature = "dev")]
+fn validate_entity_iids(server: Res<AssetServer>, collections: Res<LevelCollections>) {
+    #[derive(Deserialize)]
+    #[expect(non_snake_case, reason = "LDtk naming scheme")]
+    struct Repr {
+        layerInstances: Vec<LayerInstanceRepr>,
+    }
+
+    #[derive(Deserialize)]
+    #[expect(non_snake_case, reason = "LDtk naming scheme")]
+    struct LayerInstanceRepr {
+        #[serde(default)]
+        entityInstances: Vec<EntityInstanceRepr>,
+    }
+
+    #[derive(Deserialize)]
+    #[expect(non_snake_case, reason = "LDtk naming scheme")]
+    struct EntityInstanceRepr {
+        __identifier: String,
+        iid: String,
+        fieldInstances: Vec<FieldInstanceRepr>,
+    }
+
+    #[derive(Deserialize)]
+    struct FieldInstanceRepr {
+        __identifier: String,
+        __value: serde_json::Value,
+    }
+
+    // Entity reference fields may be single references or arrays of them.
+    fn collect_refs<'a>(value: &'a serde_json::Value, refs: &mut Vec<&'a str>) {
+        match value {
+            serde_json::Value::Object(obj) => refs.extend(obj.get("entityIid").and_then(|iid| iid.as_str())),
+            serde_json::Value::Array(values) => values.iter().for_each(|value| collect_refs(value, refs)),
+            _ => {}
+        }
+    }
+
+    let server = server.clone();
+    let collections = collections.clone();
+    IoTaskPool::get()
+        .spawn(async move {
+            let mut owners = HashMap::<String, Vec<String>>::new();
+            let mut references = Vec::new();
+
+            for (world, collection) in collections.iter() {
+                let source = match server.get_source(&collection.source) {
+                    Ok(source) => source,
+                    Err(e) => {
+                        warn!("Couldn't validate world `{world}`: {e}");
+                        continue
+                    }
+                };
+
+                for (level, path) in &collection.level_paths {
+                    let result = async {
+                        let mut bytes = Vec::new();
+                        Reader::read_to_end(&mut source.reader().read(path).await?, &mut bytes).await?;
+                        Ok::<_, BevyError>(serde_json::from_slice::<Repr>(&bytes)?)
+                    }
+                    .await;
+
+                    let repr = match result {
+                        Ok(repr) => repr,
+                        Err(e) => {
+                            warn!("Couldn't validate level `{world}:{level}`: {e}");
+                            continue
+                        }
+                    };
+
+                    for instance in repr.layerInstances.into_iter().flat_map(|layer| layer.entityInstances) {
+                        let owner = format!("`{}` in `{world}:{level}`", instance.__identifier);
+                        for field in &instance.fieldInstances {
+                            let mut refs = Vec::new();
+                            collect_refs(&field.__value, &mut refs);
+                            references.extend(refs.into_iter().map(|iid| (iid.to_owned(), format!("{owner}.{}", field.__identifier))));
+                        }
+
+                        owners.entry(instance.iid).or_default().push(owner);
+                    }
+                }
+            }
+
+            for (iid, owners) in &owners {
+                if owners.len() > 1 {
+                    warn!("Entity IID {iid} is shared by {}", owners.join(", "));
+                }
+            }
+
+            for (iid, field) in references {
+                if !owners.contains_key(&iid) {
+                    warn!("{field} references nonexistent entity IID {iid}");
+                }
+            }
+        })
+        .detach();
+}
+
+fn create_int_cell_colliders(
+    mut commands: Commands,
+    mut layers: MessageReader<LayerCreate>,
+    colliders: Res<IntCellColliders>,
+    int_grids: Query<&IntGrid>,
+) {
+    for layer in layers.read() {
+        let LayerCreate::IntGrid { identifier, entity } = layer else { continue };
+        let Ok(int_grid) = int_grids.get(*entity) else { continue };
+
+        for ((value, collision), cells) in colliders.cells(identifier, int_grid) {
+            let mut collider = commands.spawn((
+                ChildOf(*entity),
+                Name::new(format!("{identifier} = {value}")),
+                Transform2d::default(),
+                RigidBody::Static,
+                Collider::voxels(Vec2::splat(int_grid.grid_size), &cells),
+                #[cfg(feature = "dev")]
+                DebugRender::none(),
+            ));
+
+            if collision == IntCellCollision::Sensor {
+                collider.insert(Sensor);
+            }
+        }
+    }
+}
+
+/// System sets for level loading, run in the order they're declared in.
+///
+/// Entity spawners reading [`EntityCreate`] or [`LayerCreate`] should go in one of the `Spawn*`
+/// sets; commands issued by an earlier set are applied before the next set runs, so spawners that
+/// depend on other entities being fully set up (e.g. looking up another entity's components) can
+/// be put in a later set than their dependencies. Spawners within the same set run in an
+/// unspecified order. Tile colliders are created in [`SpawnEntities`](LevelSystems::SpawnEntities).
 #[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LevelSystems {
     Load,
+    /// For spawners that other spawners depend on.
+    SpawnEntitiesEarly,
+    /// The default set for spawners.
     SpawnEntities,
+    /// For spawners that depend on entities from the other sets.
+    SpawnEntitiesLate,
 }
 
 pub(super) fn plugin(app: &mut App) {
     app.init_resource::<LoadLevel>()
+        .init_resource::<VisitedLevels>()
+        .init_resource::<IntCellColliders>()
         .add_message::<EntityCreate>()
         .add_message::<LayerCreate>()
         .configure_sets(
             Update,
-            (LevelSystems::Load, LevelSystems::SpawnEntities)
+            (
+                LevelSystems::Load,
+                LevelSystems::SpawnEntitiesEarly,
+                LevelSystems::SpawnEntities,
+                LevelSystems::SpawnEntitiesLate,
+            )
                 .chain()
                 .before(ProgressSystems::UpdateTransitions)
                 .run_if(in_state(GameState::LevelLoading)),
@@ -465,7 +849,395 @@ pub(super) fn plugin(app: &mut App) {
             Update,
             (
                 load_level.in_set(LevelSystems::Load),
-                create_tile_collider.in_set(LevelSystems::SpawnEntities),
+                (create_tile_collider, create_int_cell_colliders).in_set(LevelSystems::SpawnEntities),
+            ),
+        );
+
+    #[cfg(feature = "dev")]
+    app.add_systems(OnExit(GameState::AssetLoading), validate_entity_iids);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        asset::io::{
+            AssetSource, AssetSourceId,
+            memory::{Dir, MemoryAssetReader},
+        },
+        ecs::system::RunSystemOnce,
+        state::app::StatesPlugin,
+        tasks::block_on,
+    };
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{
+        ProgressTracker,
+        entities::Hair,
+        render::atlas::{AtlasInfo, AtlasRegion, PageInfo, flip_uv_corners},
+        util::async_bridge,
+        world::{LevelCollection, Tileset},
+    };
+
+    /// Returns an app reading assets from an in-memory `test` source, holding a 32x32 pixel
+    /// `level.ldtkl` with the given layers, top-most first as LDtk writes them.
+    fn level_app(layers: Value) -> App {
+        let dir = Dir::default();
+        let level = json!({
+            "__bgColor": "#000000",
+            "pxWid": 32,
+            "pxHei": 32,
+            "layerInstances": layers,
+        });
+        dir.insert_asset_text(Path::new("level.ldtkl"), &level.to_string());
+
+        let mut app = App::new();
+        app.register_asset_source(
+            "test",
+            AssetSource::build().with_reader(move || Box::new(MemoryAssetReader { root: dir.clone() })),
+        )
+        .add_plugins((TaskPoolPlugin::default(), AssetPlugin::default(), async_bridge::plugin))
+        .init_asset::<AtlasRegion>();
+        app
+    }
+
+    /// A world holding only `level`, with a single layer definition of UID `0`.
+    fn collection(tilesets: impl IntoIterator<Item = (u32, Tileset)>) -> LevelCollectionRef {
+        LevelCollection {
+            enums: default(),
+            layers: [(0, crate::world::Layer {
+                parallax: Vec2::ZERO,
+                parallax_scale: false,
+            })]
+            .into(),
+            tilesets: tilesets.into_iter().collect(),
+            level_paths: [("level".into(), "level.ldtkl".into())].into(),
+            level_metas: default(),
+            source: AssetSourceId::from("test"),
+        }
+        .into()
+    }
+
+    /// Returns [`level_app()`] running the level plugin, with `collection` as the default world and
+    /// everything else [`load_level()`] needs. No [`GameState`] is set up, so updates only service
+    /// the async bridge until a test inserts one.
+    fn plugin_app(layers: Value, collection: LevelCollectionRef) -> App {
+        let mut app = level_app(layers);
+        app.add_plugins(plugin)
+            .init_resource::<Time>()
+            .init_resource::<ProgressTracker<GameState>>()
+            .init_resource::<NextState<GameState>>()
+            .insert_resource(LevelCollections::from_iter([(DEFAULT_WORLD.into(), collection)]));
+
+        app.world_mut().spawn((Camera::default(), MainCamera::default()));
+        app
+    }
+
+    /// Requests `level` and runs [`load_level()`] until it's done, servicing the async bridge in
+    /// between. Returns whether the level loaded.
+    fn load_through_systems(app: &mut App, level: &str) -> bool {
+        let world = app.world_mut();
+        world.resource_mut::<LoadLevel>().load(level);
+        world.run_system_once(load_level_transition).unwrap();
+
+        loop {
+            let world = app.world_mut();
+            let Ok(Ok(())) = world.run_system_once(load_level) else { return false };
+            if matches!(*world.resource::<LoadLevelProgress>(), LoadLevelProgress::Done) {
+                return true
+            }
+            app.update();
+        }
+    }
+
+    /// A 4x4 layer of 8 pixel cells, with `data` holding its `__type` and contents.
+    fn layer(identifier: &str, data: Value) -> Value {
+        let mut layer = json!({
+            "__identifier": identifier,
+            "__cWid": 4,
+            "__cHei": 4,
+            "__gridSize": 8,
+            "layerDefUid": 0,
+        });
+        layer.as_object_mut().unwrap().extend(data.as_object().unwrap().clone());
+        layer
+    }
+
+    fn entity_instance(identifier: &str, iid: &str) -> Value {
+        json!({
+            "__identifier": identifier,
+            "iid": iid,
+            "__grid": [0, 0],
+            "px": [0, 0],
+            "__pivot": [0., 0.],
+            "width": 8,
+            "height": 8,
+            "fieldInstances": [],
+        })
+    }
+
+    /// Runs [`load_level_task()`] for `level`, servicing the async bridge until it's done.
+    fn load(app: &mut App, collection: &LevelCollectionRef) -> Result<LoadLevelOutput> {
+        let world = app.world();
+        let task = AsyncComputeTaskPool::get().spawn(load_level_task(
+            "level".into(),
+            world.resource::<AssetServer>(),
+            collection,
+            world.resource::<AsyncBridge>(),
+        ));
+
+        while !task.is_finished() {
+            app.update();
+        }
+        block_on(task)
+    }
+
+    #[test]
+    fn unload_despawns_owned_entities() {
+        let mut world = World::new();
+        world.init_resource::<LoadLevel>();
+        world.init_resource::<VisitedLevels>();
+        world.init_resource::<NextState<GameState>>();
+
+        let tilemap = world.spawn((Tilemap::new(8., uvec2(2, 2)), LevelUnload(0))).id();
+        let tiles = [uvec2(0, 0), uvec2(1, 1)].map(|pos| world.spawn(Tile::new(tilemap, pos, AssetId::default())).id());
+        let hair = world.spawn((Hair::new([1., 1.], 0.1), LevelUnload(1))).id();
+        let strands = world.get::<Hair>(hair).unwrap().iter_strands().collect::<Vec<_>>();
+
+        world.resource_mut::<LoadLevel>().load("next");
+        world.run_system_once(load_level_transition).unwrap();
+        world.flush();
+
+        for entity in [tilemap, hair].into_iter().chain(tiles).chain(strands) {
+            assert!(world.get_entity(entity).is_err(), "{entity} outlived the level unload");
+        }
+        assert_eq!(world.query::<&Tile>().iter(&world).count(), 0);
+        assert_eq!(world.resource::<CurrentLevel>().identifier, "next");
+        assert!(world.resource::<CurrentLevel>().first_visit);
+        // Not loaded yet.
+        assert!(!world.resource::<VisitedLevels>().contains("next", None));
+    }
+
+    #[test]
+    fn duplicate_entity_iids_fail_to_load() {
+        let mut app = level_app(json!([
+            layer(
+                "front",
+                json!({ "__type": "Entities", "entityInstances": [entity_instance("b", "shared")] })
+            ),
+            layer(
+                "back",
+                json!({
+                    "__type": "Entities",
+                    "entityInstances": [entity_instance("a", "unique"), entity_instance("a", "shared")],
+                })
+            ),
+        ]));
+
+        let Err(e) = load(&mut app, &collection([])) else { panic!("Level with duplicate IIDs loaded") };
+        assert!(e.to_string().contains("Duplicate entity IID shared (`b` in layer front)"), "{e}");
+
+        // The same entities with distinct IIDs load fine.
+        let mut app = level_app(json!([
+            layer(
+                "front",
+                json!({ "__type": "Entities", "entityInstances": [entity_instance("b", "other")] })
+            ),
+            layer(
+                "back",
+                json!({
+                    "__type": "Entities",
+                    "entityInstances": [entity_instance("a", "unique"), entity_instance("a", "shared")],
+                })
             ),
+        ]));
+
+        let Ok(output) = load(&mut app, &collection([])) else { panic!("Level failed to load") };
+        assert_eq!(output.entity_creation.len(), 3);
+    }
+
+    #[test]
+    fn entity_field_accessors() {
+        let fields = EntityFields {
+            map: [
+                ("int".into(), EntityField::Int(3)),
+                ("tint".into(), EntityField::Color(Color::srgb(1., 0., 0.))),
+                ("behavior".into(), EntityField::Enum(Arc::new(TileProperty::Collision))),
+            ]
+            .into(),
+        };
+
+        assert!(matches!(fields.get("int"), Ok(EntityField::Int(3))));
+        assert!(fields.get("missing").unwrap_err().to_string().contains("Missing field `missing`"));
+
+        assert_eq!(fields.color("tint").unwrap(), Color::srgb(1., 0., 0.));
+        assert!(fields.color("int").unwrap_err().to_string().contains("expected a color"));
+        assert!(fields.color("missing").is_err());
+
+        assert_eq!(fields.enum_variant("behavior").unwrap(), "Collision");
+        assert!(fields.enum_variant("tint").unwrap_err().to_string().contains("expected an enum"));
+        assert!(fields.enum_variant("missing").is_err());
+    }
+
+    #[test]
+    fn int_cell_colliders() {
+        let mut app = App::new();
+        app.add_message::<LayerCreate>()
+            .register_int_cell_collision("walls", 1, IntCellCollision::Solid)
+            .register_int_cell_collision("walls", 2, IntCellCollision::Sensor)
+            .register_int_cell_collision("other", 3, IntCellCollision::Solid);
+
+        let int_grid = IntGrid {
+            grid_size: 8.,
+            dimension: uvec2(3, 2),
+            cells: vec![1, 0, 2, 3, 1, 2],
+        };
+
+        // Unregistered values, and values registered for other layers, don't collide.
+        let cells = app.world().resource::<IntCellColliders>().cells("walls", &int_grid);
+        assert_eq!(
+            cells,
+            HashMap::from_iter([
+                ((1, IntCellCollision::Solid), vec![ivec2(0, 0), ivec2(1, 1)]),
+                ((2, IntCellCollision::Sensor), vec![ivec2(2, 0), ivec2(2, 1)]),
+            ])
         );
+
+        let world = app.world_mut();
+        let layer = world.spawn(int_grid).id();
+        world.write_message(LayerCreate::IntGrid {
+            identifier: "walls".into(),
+            entity: layer,
+        });
+        world.run_system_once(create_int_cell_colliders).unwrap();
+
+        let mut colliders = world
+            .query::<(&Name, &ChildOf, &RigidBody, Has<Collider>, Has<Sensor>)>()
+            .iter(world)
+            .map(|(name, child_of, &body, collider, sensor)| {
+                assert_eq!(child_of.parent(), layer);
+                assert_eq!(body, RigidBody::Static);
+                assert!(collider);
+                (name.to_string(), sensor)
+            })
+            .collect::<Vec<_>>();
+        colliders.sort();
+
+        assert_eq!(colliders, [("walls = 1".into(), false), ("walls = 2".into(), true)]);
+    }
+
+    #[test]
+    fn flipped_tiles() {
+        let mut app = level_app(json!([layer(
+            "tiles_main",
+            json!({
+                "__type": "Tiles",
+                "__tilesetDefUid": 1,
+                "gridTiles": (0..4).map(|f| json!({ "px": [f * 8, 24], "t": 0, "f": f })).collect::<Vec<_>>(),
+            })
+        )]));
+
+        let region = app.world_mut().resource_mut::<Assets<AtlasRegion>>().add(AtlasRegion {
+            info: AtlasInfo {
+                page: PageInfo {
+                    texture: default(),
+                    texture_size: UVec2::splat(16),
+                },
+                rect: URect::new(0, 0, 8, 8),
+            },
+        });
+        let tileset = Tileset {
+            region: region.clone(),
+            tiles: [(UVec2::ZERO, region.clone())].into(),
+            properties: default(),
+            cell_size: UVec2::ONE,
+            grid_size: 8,
+        };
+
+        load(&mut app, &collection([(1, tileset)])).unwrap();
+
+        let world = app.world_mut();
+        let mut tiles = world.query::<&Tile>().iter(world).copied().collect::<Vec<_>>();
+        tiles.sort_by_key(|tile| tile.pos.x);
+        let tiles = <[Tile; 4]>::try_from(tiles).unwrap();
+        assert_eq!(
+            tiles.iter().map(|tile| tile.pos).collect::<Vec<_>>(),
+            (0..4).map(|x| uvec2(x, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(tiles.iter().map(|tile| tile.flip).collect::<Vec<_>>(), [
+            BVec2::FALSE,
+            BVec2::new(true, false),
+            BVec2::new(false, true),
+            BVec2::TRUE,
+        ]);
+
+        // Corners go bottom-left, bottom-right, top-right, top-left; the region's top-left is at
+        // the UV origin.
+        let regions = world.resource::<Assets<AtlasRegion>>();
+        let corners = tiles.map(|tile| flip_uv_corners(regions.get(tile.region).unwrap().uv_corners(), tile.flip));
+        let [bl, br, tr, tl] = [vec2(0., 0.5), vec2(0.5, 0.5), vec2(0.5, 0.), vec2(0., 0.)];
+        assert_eq!(corners, [[bl, br, tr, tl], [br, bl, tl, tr], [tl, tr, br, bl], [tr, tl, bl, br]]);
+    }
+
+    #[test]
+    fn spawn_sets_run_in_order() {
+        #[derive(Resource, Default)]
+        struct Order(Vec<&'static str>);
+
+        #[derive(Component)]
+        struct Dependency;
+
+        let mut app = plugin_app(json!([]), collection([]));
+        app.add_plugins(StatesPlugin)
+            .insert_state(GameState::LevelLoading)
+            .init_resource::<Order>()
+            .insert_resource(LoadLevelProgress::Done)
+            .add_systems(
+                Update,
+                (
+                    // Added in reverse to make sure the sets decide the order, not insertion.
+                    (|mut order: ResMut<Order>, dependencies: Query<(), With<Dependency>>| {
+                        // The early spawner's commands are applied by now.
+                        assert_eq!(dependencies.count(), 1);
+                        order.0.push("late");
+                    })
+                    .in_set(LevelSystems::SpawnEntitiesLate),
+                    (|mut commands: Commands, mut order: ResMut<Order>| {
+                        commands.spawn(Dependency);
+                        order.0.push("early");
+                    })
+                    .in_set(LevelSystems::SpawnEntitiesEarly),
+                ),
+            );
+
+        app.update();
+        assert_eq!(app.world().resource::<Order>().0, ["early", "late"]);
+    }
+
+    #[test]
+    fn visited_after_loading() {
+        let mut app = plugin_app(json!([]), collection([]));
+        let visits = |world: &mut World| {
+            let first = world.run_system_once(if_first_visit).unwrap();
+            let revisit = world.run_system_once(if_revisit).unwrap();
+            assert_ne!(first, revisit);
+
+            let visited = world.resource::<VisitedLevels>();
+            (
+                first,
+                visited.contains("level", None),
+                visited.contains(&format!("{DEFAULT_WORLD}:missing"), None),
+            )
+        };
+
+        // Failing to load doesn't count as a visit, so the level would still be new next time.
+        assert!(!load_through_systems(&mut app, "missing"));
+        assert_eq!(visits(app.world_mut()), (true, false, false));
+
+        assert!(load_through_systems(&mut app, "level"));
+        assert_eq!(visits(app.world_mut()), (true, true, false));
+
+        assert!(load_through_systems(&mut app, "level"));
+        assert_eq!(visits(app.world_mut()), (false, true, false));
+    }
 }
@@ -1,7 +1,9 @@
+use serde::Serialize;
+
 use crate::{
     GameState, ProgressFor, ProgressSystems,
     prelude::*,
-    render::atlas::{AtlasInfo, AtlasRegion, PageInfo},
+    render::atlas::{AtlasInfo, AtlasRegion, AtlasRegionSettings, PageInfo},
     util::IteratorExt,
     world::TileProperty,
 };
@@ -101,6 +103,7 @@ pub struct LevelCollection {
     pub layers: HashMap<u32, Layer>,
     pub tilesets: HashMap<u32, Tileset>,
     pub level_paths: HashMap<String, PathBuf>,
+    pub level_metas: HashMap<String, LevelMeta>,
     #[reflect(ignore)]
     pub source: AssetSourceId<'static>,
 }
@@ -141,16 +144,79 @@ impl VisitAssetDependencies for Tileset {
     }
 }
 
+/// Where a level sits in its world, and which levels touch it.
+#[derive(Reflect, Debug, Clone, Default)]
+#[reflect(Debug, Default, Clone)]
+pub struct LevelMeta {
+    /// Top-left corner of the level in world pixels, with Y pointing down as in LDtk.
+    pub world_pos: IVec2,
+    /// Identifiers of adjacent levels in each direction. Multiple levels may share one side.
+    pub neighbours: HashMap<LevelNeighbour, Vec<String>>,
+}
+
+/// Direction of a neighbouring level, as reported by LDtk.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[reflect(Debug, Clone, PartialEq, Hash)]
+pub enum LevelNeighbour {
+    North,
+    South,
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+    /// Overlaps this level at a lower world depth.
+    Below,
+    /// Overlaps this level at a higher world depth.
+    Above,
+    /// Overlaps this level at the same world depth.
+    Overlap,
+}
+
+impl FromStr for LevelNeighbour {
+    type Err = BevyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "n" => Self::North,
+            "s" => Self::South,
+            "e" => Self::East,
+            "w" => Self::West,
+            "ne" => Self::NorthEast,
+            "nw" => Self::NorthWest,
+            "se" => Self::SouthEast,
+            "sw" => Self::SouthWest,
+            "<" => Self::Below,
+            ">" => Self::Above,
+            "o" => Self::Overlap,
+            _ => Err(format!("Unknown neighbour direction `{s}`"))?,
+        })
+    }
+}
+
+/// Suggested [`LevelCollectionSettings::tileset_page`] name.
+pub const TILESET_ATLAS_PAGE: &str = "tiles";
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct LevelCollectionSettings {
+    /// Name of the atlas page to pack tilesets into, so tilemap chunks need fewer materials. Off by
+    /// default, as every atlas page is a full-size texture; a dedicated page only pays off once the
+    /// tilesets are big enough to otherwise spread across several shared pages. Set it in the
+    /// world's `.meta` file to opt in.
+    pub tileset_page: Option<String>,
+}
+
 pub struct LevelCollectionLoader {
     enums: WorldEnums,
 }
 
 impl AssetLoader for LevelCollectionLoader {
     type Asset = LevelCollection;
-    type Settings = ();
+    type Settings = LevelCollectionSettings;
     type Error = BevyError;
 
-    async fn load(&self, reader: &mut dyn Reader, _: &Self::Settings, load_context: &mut LoadContext<'_>) -> Result<Self::Asset, Self::Error> {
+    async fn load(&self, reader: &mut dyn Reader, settings: &Self::Settings, load_context: &mut LoadContext<'_>) -> Result<Self::Asset, Self::Error> {
         #[derive(Deserialize)]
         struct Repr {
             defs: DefsRepr,
@@ -209,7 +275,18 @@ impl AssetLoader for LevelCollectionLoader {
         #[expect(non_snake_case, reason = "LDtk naming scheme")]
         struct LevelPathRepr {
             identifier: String,
+            iid: String,
             externalRelPath: String,
+            worldX: i32,
+            worldY: i32,
+            __neighbours: Vec<NeighbourRepr>,
+        }
+
+        #[derive(Deserialize)]
+        #[expect(non_snake_case, reason = "LDtk naming scheme")]
+        struct NeighbourRepr {
+            levelIid: String,
+            dir: String,
         }
 
         let mut bytes = Vec::new();
@@ -229,12 +306,42 @@ impl AssetLoader for LevelCollectionLoader {
             enums.by_index.insert(enum_def.uid, ident);
         }
 
+        let level_identifiers = repr
+            .levels
+            .iter()
+            .map(|level| (level.iid.clone(), level.identifier.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let mut level_metas = HashMap::new();
+        for level in &repr.levels {
+            let mut neighbours = HashMap::<_, Vec<_>>::new();
+            for neighbour in &level.__neighbours {
+                let identifier = level_identifiers
+                    .get(&neighbour.levelIid)
+                    .ok_or_else(|| format!("Level `{}` has unknown neighbour `{}`", level.identifier, neighbour.levelIid))?;
+                neighbours.entry(neighbour.dir.parse()?).or_default().push(identifier.clone());
+            }
+
+            level_metas.insert(level.identifier.clone(), LevelMeta {
+                world_pos: ivec2(level.worldX, level.worldY),
+                neighbours,
+            });
+        }
+
         let mut tilesets = HashMap::new();
         for tileset in repr.defs.tilesets {
             let grid_size = tileset.tileGridSize;
             let tileset_path = load_context.asset_path().resolve_embed(&tileset.relPath)?;
 
-            let region = load_context.loader().immediate().load::<AtlasRegion>(&tileset_path).await?;
+            let region = load_context
+                .loader()
+                .immediate()
+                .with_settings({
+                    let page = settings.tileset_page.clone();
+                    move |settings: &mut AtlasRegionSettings| settings.page = page.clone()
+                })
+                .load::<AtlasRegion>(&tileset_path)
+                .await?;
             let region_ref = region.get();
 
             if region_ref.rect.size() % grid_size != UVec2::ZERO {
@@ -300,6 +407,7 @@ impl AssetLoader for LevelCollectionLoader {
                     load_context.asset_path().resolve_embed(&repr.externalRelPath)?.path().into(),
                 ))
             })?,
+            level_metas,
             source: load_context.asset_path().source().clone_owned(),
         })
     }
@@ -319,11 +427,135 @@ impl Deref for LevelCollectionRef {
     }
 }
 
+impl From<LevelCollection> for LevelCollectionRef {
+    fn from(value: LevelCollection) -> Self {
+        Self(Arc::new(value))
+    }
+}
+
+/// Name of the world that unqualified level identifiers resolve in. See [`LevelId`].
+pub const DEFAULT_WORLD: &str = "world";
+
+/// Asset paths of every LDtk world to load during [`GameState::AssetLoading`], keyed by world name.
+/// Always contains [`DEFAULT_WORLD`] unless removed; insert more before [`Startup`] to load them.
+#[derive(Resource, Debug, Clone, Deref, DerefMut)]
+pub struct LevelWorldPaths(pub HashMap<String, AssetPath<'static>>);
+impl Default for LevelWorldPaths {
+    fn default() -> Self {
+        Self([(DEFAULT_WORLD.into(), "levels/world.ldtk".into())].into())
+    }
+}
+
+/// Every loaded LDtk world, keyed by name.
+#[derive(Resource, Clone, Default)]
+pub struct LevelCollections(HashMap<String, LevelCollectionRef>);
+impl FromIterator<(String, LevelCollectionRef)> for LevelCollections {
+    fn from_iter<T: IntoIterator<Item = (String, LevelCollectionRef)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl LevelCollections {
+    pub fn get(&self, world: &str) -> Option<&LevelCollectionRef> {
+        self.0.get(world)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &LevelCollectionRef)> {
+        self.0.iter().map(|(name, collection)| (name.as_str(), collection))
+    }
+
+    /// Spells out the world of an unqualified `id`. That's [`DEFAULT_WORLD`] if it has the level,
+    /// otherwise the only other world that does. Ambiguous or unknown levels stay in the default
+    /// world, so they fail to load with a proper error.
+    pub fn qualify(&self, id: LevelId) -> LevelId {
+        if id.world.is_some()
+            || self
+                .get(DEFAULT_WORLD)
+                .is_some_and(|collection| collection.level_paths.contains_key(&id.level))
+        {
+            return id.qualified()
+        }
+
+        let mut worlds = self.iter().filter(|(.., collection)| collection.level_paths.contains_key(&id.level));
+        match (worlds.next(), worlds.next()) {
+            (Some((world, ..)), None) => LevelId {
+                world: Some(world.into()),
+                level: id.level,
+            },
+            _ => id.qualified(),
+        }
+    }
+
+    /// Returns the world `id` resolves in, failing if that world isn't loaded.
+    pub fn resolve(&self, id: &LevelId) -> Result<&LevelCollectionRef> {
+        let world = id.world();
+        Ok(self
+            .get(world)
+            .ok_or_else(|| format!("World `{world}` isn't loaded, required by level `{id}`"))?)
+    }
+}
+
+/// A level identifier, optionally qualified by its world's name as `world:level`. Unqualified
+/// identifiers refer to levels in [`DEFAULT_WORLD`], or to the only other world that has the level
+/// when loaded through [`LoadLevel`](crate::world::LoadLevel); see [`LevelCollections::qualify()`].
+///
+/// Anything that persists level identifiers (e.g. [`VisitedLevels`](crate::world::VisitedLevels))
+/// stores the qualified form, so references stay valid across worlds sharing level names.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LevelId {
+    pub world: Option<String>,
+    pub level: String,
+}
+
+impl LevelId {
+    pub fn world(&self) -> &str {
+        self.world.as_deref().unwrap_or(DEFAULT_WORLD)
+    }
+
+    /// Returns this identifier with its world spelled out.
+    pub fn qualified(mut self) -> Self {
+        self.world.get_or_insert_with(|| DEFAULT_WORLD.into());
+        self
+    }
+}
+
+impl From<&str> for LevelId {
+    fn from(value: &str) -> Self {
+        match value.split_once(':') {
+            Some((world, level)) => Self {
+                world: Some(world.into()),
+                level: level.into(),
+            },
+            None => Self {
+                world: None,
+                level: value.into(),
+            },
+        }
+    }
+}
+
+impl From<String> for LevelId {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl fmt::Display for LevelId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.world {
+            Some(world) => write!(f, "{world}:{}", self.level),
+            None => write!(f, "{}", self.level),
+        }
+    }
+}
+
 #[derive(Resource)]
-struct LevelCollectionHandle(Handle<LevelCollection>);
+struct LevelCollectionHandles(HashMap<String, Handle<LevelCollection>>);
 
-fn init_level_collection(mut commands: Commands, server: Res<AssetServer>) {
-    commands.insert_resource(LevelCollectionHandle(server.load("levels/world.ldtk")));
+fn init_level_collection(mut commands: Commands, server: Res<AssetServer>, paths: Res<LevelWorldPaths>) {
+    commands.insert_resource(LevelCollectionHandles(
+        paths.iter().map(|(world, path)| (world.clone(), server.load(path.clone()))).collect(),
+    ));
 }
 
 fn query_level_collection(
@@ -331,32 +563,55 @@ fn query_level_collection(
     progress: ProgressFor<GameState>,
     server: Res<AssetServer>,
     mut assets: ResMut<Assets<LevelCollection>>,
-    handle: Option<Res<LevelCollectionHandle>>,
+    handles: Option<Res<LevelCollectionHandles>>,
 ) -> Result {
-    let Some(handle) = handle else {
+    let Some(handles) = handles else {
         progress.update([2, 2]);
         return Ok(())
     };
 
-    match (server.load_state(&handle.0), server.recursive_dependency_load_state(&handle.0)) {
-        (LoadState::Failed(e), ..) | (.., RecursiveDependencyLoadState::Failed(e)) => Err(e)?,
-        (LoadState::NotLoaded, ..) | (.., RecursiveDependencyLoadState::NotLoaded) => Err("Level collection handle got dropped")?,
-        (LoadState::Loaded, RecursiveDependencyLoadState::Loaded) => {
-            let collection = LevelCollectionRef(Arc::new(assets.remove(&handle.0).ok_or("Level collection unexpectedly removed")?));
-            commands.insert_resource(collection);
+    // An empty world list has nothing to load; report it as done instead of stalling, with no
+    // worlds to resolve levels in.
+    if handles.0.is_empty() {
+        commands.insert_resource(LevelCollections::default());
+        commands.remove_resource::<LevelCollectionHandles>();
+        progress.update([2, 2]);
+        return Ok(())
+    }
+
+    let mut current = 0;
+    for handle in handles.0.values() {
+        current += match (server.load_state(handle), server.recursive_dependency_load_state(handle)) {
+            (LoadState::Failed(e), ..) | (.., RecursiveDependencyLoadState::Failed(e)) => Err(e)?,
+            (LoadState::NotLoaded, ..) | (.., RecursiveDependencyLoadState::NotLoaded) => Err("Level collection handle got dropped")?,
+            (LoadState::Loaded, RecursiveDependencyLoadState::Loaded) => 2,
+            (LoadState::Loaded, ..) | (.., RecursiveDependencyLoadState::Loaded) => 1,
+            (LoadState::Loading, RecursiveDependencyLoadState::Loading) => 0,
+        };
+    }
 
-            commands.remove_resource::<LevelCollectionHandle>();
-            progress.update([2, 2]);
+    let total = handles.0.len() * 2;
+    if current == total {
+        let mut collections = LevelCollections::default();
+        for (world, handle) in &handles.0 {
+            let collection = assets
+                .remove(handle)
+                .ok_or_else(|| format!("Level collection `{world}` unexpectedly removed"))?;
+            collections.0.insert(world.clone(), collection.into());
         }
-        (LoadState::Loaded, ..) | (.., RecursiveDependencyLoadState::Loaded) => progress.update([1, 2]),
-        (LoadState::Loading, RecursiveDependencyLoadState::Loading) => progress.update([0, 2]),
-    };
+
+        commands.insert_resource(collections);
+        commands.remove_resource::<LevelCollectionHandles>();
+    }
+
+    progress.update([current, total]);
     Ok(())
 }
 
 pub(super) fn plugin(app: &mut App) {
     app.init_asset::<LevelCollection>()
         .register_asset_reflect::<LevelCollection>()
+        .init_resource::<LevelWorldPaths>()
         .register_asset_loader(LevelCollectionLoader {
             enums: WorldEnums::default().with::<TileProperty>("tile_properties"),
         })
@@ -368,3 +623,126 @@ pub(super) fn plugin(app: &mut App) {
                 .before(ProgressSystems::UpdateTransitions),
         );
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::io::{
+        AssetSource,
+        memory::{Dir, MemoryAssetReader},
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    /// Loads a world with the given levels from an in-memory `test` asset source.
+    fn load_world(levels: serde_json::Value) -> Result<LevelCollection> {
+        let dir = Dir::default();
+        let world = json!({
+            "defs": { "layers": [], "enums": [], "tilesets": [] },
+            "levels": levels,
+        });
+        dir.insert_asset_text(Path::new("world.ldtk"), &world.to_string());
+
+        let mut app = App::new();
+        app.register_asset_source(
+            "test",
+            AssetSource::build().with_reader(move || Box::new(MemoryAssetReader { root: dir.clone() })),
+        )
+        .add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+        .init_asset::<AtlasRegion>()
+        .init_asset::<LevelCollection>()
+        .register_asset_loader(LevelCollectionLoader { enums: default() });
+
+        let handle = app.world().resource::<AssetServer>().load::<LevelCollection>("test://world.ldtk");
+        loop {
+            match app.world().resource::<AssetServer>().load_state(&handle) {
+                LoadState::Loaded => break,
+                LoadState::Failed(e) => Err(e)?,
+                _ => app.update(),
+            }
+        }
+
+        Ok(app.world_mut().resource_mut::<Assets<LevelCollection>>().remove(&handle).unwrap())
+    }
+
+    fn level(identifier: &str, iid: &str, world_pos: [i32; 2], neighbours: &[(&str, &str)]) -> serde_json::Value {
+        json!({
+            "identifier": identifier,
+            "iid": iid,
+            "externalRelPath": format!("world/{identifier}.ldtkl"),
+            "worldX": world_pos[0],
+            "worldY": world_pos[1],
+            "__neighbours": neighbours
+                .iter()
+                .map(|&(iid, dir)| json!({ "levelIid": iid, "dir": dir }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// A world with only level paths, named after their identifiers.
+    fn paths_only(levels: &[&str]) -> LevelCollectionRef {
+        LevelCollection {
+            enums: default(),
+            layers: default(),
+            tilesets: default(),
+            level_paths: levels.iter().map(|&level| (level.into(), format!("{level}.ldtkl").into())).collect(),
+            level_metas: default(),
+            source: default(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn loads_level_neighbours() {
+        let world = load_world(json!([
+            level("start", "iid-start", [0, 0], &[("iid-cave", "e"), ("iid-sky", "n")]),
+            level("cave", "iid-cave", [256, 64], &[("iid-start", "w"), ("iid-secret", "o")]),
+            level("sky", "iid-sky", [0, -128], &[("iid-start", "s")]),
+            level("secret", "iid-secret", [256, 64], &[("iid-cave", "o")]),
+        ]))
+        .unwrap();
+
+        let start = &world.level_metas["start"];
+        assert_eq!(start.world_pos, ivec2(0, 0));
+        assert_eq!(start.neighbours.len(), 2);
+        assert_eq!(start.neighbours[&LevelNeighbour::East], ["cave"]);
+        assert_eq!(start.neighbours[&LevelNeighbour::North], ["sky"]);
+
+        let cave = &world.level_metas["cave"];
+        assert_eq!(cave.world_pos, ivec2(256, 64));
+        assert_eq!(cave.neighbours[&LevelNeighbour::West], ["start"]);
+        assert_eq!(cave.neighbours[&LevelNeighbour::Overlap], ["secret"]);
+
+        assert_eq!(world.level_metas["sky"].world_pos, ivec2(0, -128));
+        assert_eq!(world.level_paths["cave"], Path::new("world/cave.ldtkl"));
+    }
+
+    #[test]
+    fn unknown_neighbours_fail_to_load() {
+        let world = load_world(json!([level("start", "iid-start", [0, 0], &[("iid-gone", "e")])]));
+        assert!(world.unwrap_err().to_string().contains("unknown neighbour `iid-gone`"));
+    }
+
+    #[test]
+    fn resolves_levels_in_named_worlds() {
+        let collections = [
+            (DEFAULT_WORLD.into(), paths_only(&["start", "shared"])),
+            ("caves".into(), paths_only(&["deep", "shared"])),
+        ]
+        .into_iter()
+        .collect::<LevelCollections>();
+
+        // Unqualified levels only in a named world resolve there.
+        let id = collections.qualify("deep".into());
+        assert_eq!(id.to_string(), "caves:deep");
+        assert_eq!(collections.resolve(&id).unwrap().level_paths["deep"], Path::new("deep.ldtkl"));
+
+        // Levels in the default world, or qualified ones, stay where they are.
+        assert_eq!(collections.qualify("shared".into()).to_string(), "world:shared");
+        let id = collections.qualify("caves:shared".into());
+        assert_eq!(id.to_string(), "caves:shared");
+        assert!(collections.resolve(&id).is_ok());
+
+        assert!(collections.resolve(&"mines:deep".into()).is_err());
+    }
+}
@@ -1,8 +1,13 @@
+use std::collections::VecDeque;
+
 use crate::{
     GameState,
-    math::Transform2d,
+    math::{GlobalTransform2d, Transform2d},
     prelude::*,
-    render::{MAIN_LAYER, MainCamera, atlas::AtlasRegion},
+    render::{
+        DeterministicRender, MAIN_LAYER, MainCamera, PixelScale,
+        atlas::{AtlasRegion, flip_uv_corners},
+    },
     util::ecs::ReflectComponentPtr,
     world::{TileId, TileProperty, TilemapProperties},
 };
@@ -17,6 +22,8 @@ pub struct Tile {
     pub tilemap: Entity,
     pub pos: UVec2,
     pub region: AssetId<AtlasRegion>,
+    /// Mirrors the region's texture horizontally and/or vertically.
+    pub flip: BVec2,
 }
 
 impl Tile {
@@ -25,9 +32,15 @@ impl Tile {
             tilemap,
             pos,
             region: region.into(),
+            flip: BVec2::FALSE,
         }
     }
 
+    pub fn with_flip(mut self, flip: BVec2) -> Self {
+        self.flip = flip;
+        self
+    }
+
     pub fn index(self, dimension: UVec2) -> usize {
         self.pos.y as usize * dimension.x as usize + self.pos.x as usize
     }
@@ -73,7 +86,9 @@ fn on_tile_replace(
     }
 
     let &tile = world.get::<Tile>(entity).unwrap();
-    let mut tilemap = world.get_mut::<Tilemap>(tile.tilemap).expect("Missing `Tilemap` component");
+    // The tilemap may have been despawned first, e.g. when unloading levels; there's nothing to
+    // update in that case.
+    let Some(mut tilemap) = world.get_mut::<Tilemap>(tile.tilemap) else { return };
 
     let dim = tilemap.dimension;
     let current_tile = tilemap
@@ -88,6 +103,39 @@ fn on_tile_replace(
     }
 }
 
+/// Cycles the region of a [`Tile`] through `frames`. Each frame change remeshes the tile's chunk,
+/// so keep animated tiles sparse and their frame rates low.
+#[derive(Reflect, Component, Debug, Clone)]
+#[require(TileAnimationElapsed)]
+#[reflect(Component, Debug, Clone)]
+pub struct TileAnimation {
+    pub frames: Vec<AssetId<AtlasRegion>>,
+    pub fps: f32,
+}
+
+impl TileAnimation {
+    pub fn frame_at(&self, elapsed: Duration) -> Option<AssetId<AtlasRegion>> {
+        let index = (elapsed.as_secs_f64() * self.fps.max(0.) as f64) as usize;
+        self.frames.get(index.checked_rem(self.frames.len())?).copied()
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, Copy)]
+struct TileAnimationElapsed(Duration);
+
+fn update_tile_animations(mut commands: Commands, time: Res<Time>, tiles: Query<(Entity, &Tile, &TileAnimation, &mut TileAnimationElapsed)>) {
+    let dt = time.delta();
+    for (entity, &tile, anim, mut elapsed) in tiles {
+        elapsed.0 += dt;
+        if let Some(region) = anim.frame_at(elapsed.0)
+            && region != tile.region
+        {
+            // `Tile` is immutable; reinserting it marks its chunk as changed.
+            commands.entity(entity).insert(Tile { region, ..tile });
+        }
+    }
+}
+
 #[derive(Reflect, Component, MapEntities, Debug, Clone)]
 #[require(TilemapChunks, TilemapParallax, Transform2d, Visibility)]
 #[component(on_despawn = on_tilemap_despawn)]
@@ -119,6 +167,14 @@ impl Tilemap {
         self.dimension
     }
 
+    pub fn get(&self, pos: UVec2) -> Option<Entity> {
+        if pos.cmpge(self.dimension).any() {
+            return None
+        }
+
+        self.tiles[pos.y as usize * self.dimension.x as usize + pos.x as usize]
+    }
+
     pub fn clear(&mut self, commands: &mut Commands) {
         for tile in &mut self.tiles {
             if let Some(entity) = tile.take() {
@@ -144,10 +200,110 @@ impl Tilemap {
         }
     }
 
+    /// Spawns tiles with `region` on every cell in `min..=max`, clamped to the tilemap's bounds.
+    /// Existing tiles on those cells are despawned once the commands are applied. Returns the edits
+    /// made, e.g. to [record](TileHistory::record) them.
+    pub fn fill_rect(
+        &self,
+        tilemap: Entity,
+        min: UVec2,
+        max: UVec2,
+        region: impl Into<AssetId<AtlasRegion>>,
+        tiles: &Query<&Tile>,
+        commands: &mut Commands,
+    ) -> Vec<TileEdit> {
+        if self.dimension.cmpeq(UVec2::ZERO).any() {
+            return Vec::new()
+        }
+
+        let max = max.min(self.dimension - 1);
+        let region = region.into();
+
+        let mut edits = Vec::new();
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                edits.push(self.paint(tilemap, uvec2(x, y), region, tiles, commands));
+            }
+        }
+
+        edits
+    }
+
+    /// Spawns tiles with `region` on every cell orthogonally connected to `start` that has the same
+    /// region as `start`, treating empty cells as a region of their own. Returns the edits made.
+    pub fn flood_fill(
+        &self,
+        tilemap: Entity,
+        start: UVec2,
+        region: impl Into<AssetId<AtlasRegion>>,
+        tiles: &Query<&Tile>,
+        commands: &mut Commands,
+    ) -> Vec<TileEdit> {
+        let region = region.into();
+        let width = self.dimension.x as usize;
+        let region_at = |pos: UVec2| {
+            self.tiles[pos.y as usize * width + pos.x as usize]
+                .and_then(|e| tiles.get(e).ok())
+                .map(|tile| tile.region)
+        };
+
+        if start.cmpge(self.dimension).any() {
+            return Vec::new()
+        }
+
+        let target = region_at(start);
+        if target == Some(region) {
+            return Vec::new()
+        }
+
+        let mut edits = Vec::new();
+        let mut visited = vec![false; self.tiles.len()];
+        let mut stack = vec![start];
+        while let Some(pos) = stack.pop() {
+            let index = pos.y as usize * width + pos.x as usize;
+            if mem::replace(&mut visited[index], true) || region_at(pos) != target {
+                continue
+            }
+
+            edits.push(self.paint(tilemap, pos, region, tiles, commands));
+            stack.extend(
+                [
+                    pos.x.checked_sub(1).map(|x| uvec2(x, pos.y)),
+                    pos.y.checked_sub(1).map(|y| uvec2(pos.x, y)),
+                    Some(pos + UVec2::X),
+                    Some(pos + UVec2::Y),
+                ]
+                .into_iter()
+                .flatten()
+                .filter(|next| next.cmplt(self.dimension).all()),
+            );
+        }
+
+        edits
+    }
+
+    fn paint(&self, tilemap: Entity, pos: UVec2, region: AssetId<AtlasRegion>, tiles: &Query<&Tile>, commands: &mut Commands) -> TileEdit {
+        let old = self.get(pos).and_then(|e| tiles.get(e).ok()).map(TileLook::from);
+        commands.spawn(Tile::new(tilemap, pos, region));
+
+        TileEdit {
+            tilemap,
+            pos,
+            old,
+            new: Some(region.into()),
+        }
+    }
+
     pub fn change_chunk(&mut self, pos: UVec2) {
         self.changed_chunks.insert(pos / TILEMAP_CHUNK_SIZE);
     }
 
+    pub fn change_all_chunks(&mut self) {
+        let chunks = (self.dimension + (TILEMAP_CHUNK_SIZE - 1)) / TILEMAP_CHUNK_SIZE;
+        self.changed_chunks
+            .extend((0..chunks.y).flat_map(|y| (0..chunks.x).map(move |x| uvec2(x, y))));
+    }
+
     pub fn chunk_size_at(&self, pos: UVec2) -> UVec2 {
         let start = (pos * TILEMAP_CHUNK_SIZE).min(self.dimension);
         let end = ((pos + 1) * TILEMAP_CHUNK_SIZE).min(self.dimension);
@@ -165,6 +321,12 @@ impl Tilemap {
         self.changed_chunks.iter().copied()
     }
 
+    /// Returns the position of the cell containing `world_pos`, given the tilemap's transform.
+    pub fn tile_at(&self, trns: &GlobalTransform2d, world_pos: Vec2) -> Option<UVec2> {
+        let local_pos = trns.affine.inverse().transform_point2(world_pos) / self.grid_size;
+        (local_pos.cmpge(Vec2::ZERO).all() && local_pos.cmplt(self.dimension.as_vec2()).all()).then(|| local_pos.as_uvec2())
+    }
+
     pub fn iter_chunk(&self, chunk: UVec2) -> impl Iterator<Item = (UVec2, Option<Entity>)> {
         let [x, y] = chunk.to_array();
         let width = self.dimension.x;
@@ -181,6 +343,156 @@ impl Tilemap {
     }
 }
 
+/// A single tile change, where `None` means an empty cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileEdit {
+    pub tilemap: Entity,
+    pub pos: UVec2,
+    pub old: Option<TileLook>,
+    pub new: Option<TileLook>,
+}
+
+/// How a [`Tile`] is drawn, as recorded in [`TileEdit`]s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileLook {
+    pub region: AssetId<AtlasRegion>,
+    pub flip: BVec2,
+}
+
+impl From<AssetId<AtlasRegion>> for TileLook {
+    fn from(region: AssetId<AtlasRegion>) -> Self {
+        Self { region, flip: BVec2::FALSE }
+    }
+}
+
+impl From<&Tile> for TileLook {
+    fn from(tile: &Tile) -> Self {
+        Self {
+            region: tile.region,
+            flip: tile.flip,
+        }
+    }
+}
+
+/// Undo/redo history of tile edits. Each recorded step may hold many edits, e.g. the ones returned
+/// by a single [`Tilemap::fill_rect()`] or [`Tilemap::flood_fill()`], and is undone or redone as a
+/// whole. Edits are replayed by spawning or
+/// despawning [`Tile`]s, so tilemap chunks are invalidated as usual.
+#[derive(Resource, Debug)]
+pub struct TileHistory {
+    undo: VecDeque<Vec<TileEdit>>,
+    redo: Vec<Vec<TileEdit>>,
+    capacity: usize,
+}
+
+impl Default for TileHistory {
+    fn default() -> Self {
+        Self::with_capacity(256)
+    }
+}
+
+impl TileHistory {
+    /// Creates an empty history that forgets the oldest steps beyond `capacity` steps.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Records an already-applied step, discarding any undone steps. Multiple edits to the same
+    /// cell are merged into one, from the first edit's `old` to the last edit's `new`.
+    pub fn record(&mut self, edits: impl IntoIterator<Item = TileEdit>) {
+        let mut merged = Vec::<TileEdit>::new();
+        let mut indices = HashMap::new();
+        for edit in edits {
+            if let Some(&index) = indices.get(&(edit.tilemap, edit.pos)) {
+                merged[index].new = edit.new;
+            } else {
+                indices.insert((edit.tilemap, edit.pos), merged.len());
+                merged.push(edit);
+            }
+        }
+
+        merged.retain(|edit| edit.old != edit.new);
+        if merged.is_empty() {
+            return
+        }
+
+        self.redo.clear();
+        self.undo.push_back(merged);
+        while self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Reverts the latest step. Returns `false` if there's nothing to undo.
+    pub fn undo(&mut self, tilemaps: &Query<&Tilemap>, commands: &mut Commands) -> bool {
+        let Some(edits) = self.undo.pop_back() else { return false };
+        for edit in edits.iter().rev() {
+            Self::apply(edit.tilemap, edit.pos, edit.old, tilemaps, commands);
+        }
+
+        self.redo.push(edits);
+        true
+    }
+
+    /// Reapplies the latest undone step. Returns `false` if there's nothing to redo.
+    pub fn redo(&mut self, tilemaps: &Query<&Tilemap>, commands: &mut Commands) -> bool {
+        let Some(edits) = self.redo.pop() else { return false };
+        for edit in &edits {
+            Self::apply(edit.tilemap, edit.pos, edit.new, tilemaps, commands);
+        }
+
+        self.undo.push_back(edits);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    fn apply(tilemap: Entity, pos: UVec2, look: Option<TileLook>, tilemaps: &Query<&Tilemap>, commands: &mut Commands) {
+        // Edits on tilemaps that have since been despawned, e.g. from unloaded levels, are skipped.
+        let Ok(tilemap_ref) = tilemaps.get(tilemap) else { return };
+        match look {
+            // The insert hook despawns whatever tile was there before.
+            Some(TileLook { region, flip }) => {
+                commands.spawn(Tile::new(tilemap, pos, region).with_flip(flip));
+            }
+            None => {
+                if let Some(tile) = tilemap_ref.get(pos) {
+                    commands.entity(tile).try_despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Returns the position of the tile on `tilemap` under the cursor of `window`, as rendered by the
+/// [`MainCamera`] through the pixelated canvas, or `None` if the cursor is outside of either.
+pub fn cursor_tile(
+    window: &Window,
+    scale: PixelScale,
+    (camera, camera_trns): (&Camera, &GlobalTransform),
+    (tilemap, tilemap_trns): (&Tilemap, &GlobalTransform2d),
+) -> Option<UVec2> {
+    // The main camera renders to the canvas, which is `scale` times smaller than the window.
+    let canvas_pos = scale.window_to_canvas(window.physical_cursor_position()?, window.physical_size())?;
+    let world_pos = camera.viewport_to_world_2d(camera_trns, canvas_pos).ok()?;
+    tilemap.tile_at(tilemap_trns, world_pos)
+}
+
 fn on_tilemap_despawn(mut world: DeferredWorld, HookContext { entity, .. }: HookContext) {
     let (entities, mut commands) = world.entities_and_commands();
     for &tile in entities.get(entity).unwrap().get::<Tilemap>().unwrap().tiles.iter().flatten() {
@@ -194,6 +506,19 @@ fn clear_tilemap_changed_chunks(tilemaps: Query<&mut Tilemap>) {
     }
 }
 
+/// How many texels tile UVs are shrunk by on each side when meshing tilemap chunks. `0.5` (a
+/// half-texel) prevents seams from neighboring atlas regions bleeding in at fractional camera
+/// scales, at the cost of slightly cropping the tile edges. Defaults to `0`, i.e. exact edges.
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Deref, DerefMut)]
+#[reflect(Resource, Debug, Default, FromWorld, Clone, PartialEq)]
+pub struct TilemapUvInset(pub f32);
+
+fn remesh_tilemaps_on_inset_change(tilemaps: Query<&mut Tilemap>) {
+    for mut tilemap in tilemaps {
+        tilemap.change_all_chunks();
+    }
+}
+
 #[derive(Reflect, Component, Debug, Default, Clone)]
 #[reflect(Component, Debug, Default, FromWorld, Clone)]
 pub struct TilemapChunks {
@@ -214,9 +539,13 @@ fn update_tilemap_chunks(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     regions: Res<Assets<AtlasRegion>>,
+    uv_inset: Res<TilemapUvInset>,
+    deterministic: Res<DeterministicRender>,
     tiles: Query<(&Tile, Option<&TileId>)>,
 ) {
     let regions = regions.into_inner();
+    let uv_inset = **uv_inset;
+    let deterministic = **deterministic;
     let mesh_handle_allocator = &meshes.get_handle_provider();
     let material_handle_allocator = &materials.get_handle_provider();
 
@@ -233,19 +562,19 @@ fn update_tilemap_chunks(
                     }
                 }
 
-                for chunk_pos in tilemap.iter_changed_chunks() {
+                let mut changed_chunks = tilemap.iter_changed_chunks().collect::<Vec<_>>();
+                if deterministic {
+                    changed_chunks.sort_unstable_by_key(|pos| (pos.y, pos.x));
+                }
+
+                for chunk_pos in changed_chunks {
+                    let chunk_size = tilemap.chunk_size_at(chunk_pos);
                     let chunk_entity = {
                         let e = commands
-                            .spawn((
-                                ChildOf(tilemap_entity),
-                                TilemapChunk {
-                                    size: tilemap.chunk_size_at(chunk_pos),
-                                },
-                                Transform2d {
-                                    translation: (chunk_pos.as_vec2() * TILEMAP_CHUNK_SIZE as f32).extend(0.),
-                                    ..default()
-                                },
-                            ))
+                            .spawn((ChildOf(tilemap_entity), TilemapChunk { size: chunk_size }, Transform2d {
+                                translation: (chunk_pos.as_vec2() * TILEMAP_CHUNK_SIZE as f32 * tilemap.grid_size).extend(0.),
+                                ..default()
+                            }))
                             .id();
 
                         if let Some(old_chunk_entity) = chunks.chunk_entities.insert(chunk_pos, e) {
@@ -269,7 +598,7 @@ fn update_tilemap_chunks(
                             indices.extend([i, i + 1, i + 2, i + 2, i + 3, i]);
 
                             positions.extend([[bx, by, 0.], [tx, by, 0.], [tx, ty, 0.], [bx, ty, 0.]]);
-                            uvs.extend(region.uv_corners().map(|uv| uv.to_array()));
+                            uvs.extend(flip_uv_corners(region.uv_corners_inset(uv_inset), tile.flip).map(|uv| uv.to_array()));
                             colors.extend(iter::repeat_n(
                                 match tilemap_properties
                                     .zip(tile_id)
@@ -283,6 +612,11 @@ fn update_tilemap_chunks(
                             ));
                         }
 
+                        let mut for_image = for_image.into_iter().collect::<Vec<_>>();
+                        if deterministic {
+                            for_image.sort_unstable_by_key(|(image, ..)| image.id());
+                        }
+
                         for_image
                             .into_iter()
                             .map(move |(image, (positions, uvs, colors, indices))| {
@@ -308,7 +642,9 @@ fn update_tilemap_chunks(
                                     },
                                     (
                                         ChildOf(chunk_entity),
-                                        Aabb::from_min_max(Vec3::ZERO, Vec2::splat(TILEMAP_CHUNK_SIZE as f32 * tilemap.grid_size).extend(0.)),
+                                        // Chunks are children of their tilemap, so frustum culling accounts for the
+                                        // `TilemapParallax` offset and scale through the propagated transform.
+                                        Aabb::from_min_max(Vec3::ZERO, (chunk_size.as_vec2() * tilemap.grid_size).extend(0.)),
                                         Mesh2d(mesh_handle),
                                         MeshMaterial2d(material_handle),
                                         MAIN_LAYER,
@@ -329,6 +665,30 @@ fn update_tilemap_chunks(
     }
 }
 
+/// Hides chunks that are entirely outside of the [`MainCamera`]'s view. The bounds come from each
+/// chunk's propagated transform, so [`TilemapParallax`] offsets and scales are accounted for.
+fn cull_tilemap_chunks(
+    scale: Res<PixelScale>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<&GlobalTransform, With<MainCamera>>,
+    tilemaps: Query<&Tilemap>,
+    chunks: Query<(&TilemapChunk, &ChildOf, &GlobalTransform2d, &mut Visibility)>,
+) {
+    let view = Rect::from_center_size(camera.translation().truncate(), scale.canvas_size(window.physical_size()).as_vec2());
+    for (chunk, child_of, trns, mut visibility) in chunks {
+        let Ok(tilemap) = tilemaps.get(child_of.parent()) else { continue };
+        let bounds = Rect::from_corners(
+            trns.transform_point2(Vec2::ZERO),
+            trns.transform_point2(chunk.size.as_vec2() * tilemap.grid_size),
+        );
+
+        visibility.set_if_neq(match view.intersect(bounds).is_empty() {
+            false => Visibility::Inherited,
+            true => Visibility::Hidden,
+        });
+    }
+}
+
 #[derive(Reflect, Component, Debug, Clone, Copy)]
 #[require(Transform2d)]
 #[reflect(Component, ComponentPtr, Debug, Default, FromWorld, Clone)]
@@ -365,17 +725,401 @@ pub enum TilemapSystems {
 }
 
 pub(super) fn plugin(app: &mut App) {
-    app.add_systems(
+    let remesh = (
+        update_tilemap_chunks,
+        clear_tilemap_changed_chunks.in_set(TilemapSystems::ClearChangedChunks),
+    )
+        .chain();
+    // Changed chunks pile up while frozen, and are remeshed once extraction resumes.
+    #[cfg(feature = "dev")]
+    let remesh = remesh.run_if(|freeze: Res<crate::render::RenderFreeze>| !freeze.is_skipping());
+
+    app.init_resource::<TilemapUvInset>().init_resource::<TileHistory>().add_systems(
         PostUpdate,
         (
             (
-                update_tilemap_chunks,
-                clear_tilemap_changed_chunks.in_set(TilemapSystems::ClearChangedChunks),
+                update_tile_animations,
+                remesh_tilemaps_on_inset_change.run_if(resource_changed::<TilemapUvInset>),
+                remesh,
             )
                 .chain()
                 // TODO use computed state for `InGame`.
                 .run_if(in_state(GameState::InGame { paused: false })),
             update_tilemap_parallax.before(TransformSystems::Propagate),
+            cull_tilemap_chunks
+                .after(TransformSystems::Propagate)
+                .before(VisibilitySystems::VisibilityPropagate),
         ),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::RunSystemOnce, tasks::TaskPool};
+
+    use super::*;
+    use crate::render::atlas::{AtlasInfo, PageInfo};
+
+    const A: AssetId<AtlasRegion> = AssetId::Uuid { uuid: Uuid::from_u128(1) };
+    const B: AssetId<AtlasRegion> = AssetId::Uuid { uuid: Uuid::from_u128(2) };
+
+    fn spawn_tilemap(world: &mut World, dimension: UVec2) -> Entity {
+        world.spawn(Tilemap::new(8., dimension)).id()
+    }
+
+    /// Returns each cell's region, row by row from the bottom.
+    fn regions(world: &World, tilemap: Entity) -> Vec<Vec<Option<AssetId<AtlasRegion>>>> {
+        let tilemap = world.get::<Tilemap>(tilemap).unwrap();
+        (0..tilemap.dimension().y)
+            .map(|y| {
+                (0..tilemap.dimension().x)
+                    .map(|x| tilemap.get(uvec2(x, y)).map(|tile| world.get::<Tile>(tile).unwrap().region))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn fill_rect(world: &mut World, tilemap: Entity, min: UVec2, max: UVec2, region: AssetId<AtlasRegion>) -> Vec<TileEdit> {
+        world
+            .run_system_once(move |tilemaps: Query<&Tilemap>, tiles: Query<&Tile>, mut commands: Commands| {
+                tilemaps.get(tilemap).unwrap().fill_rect(tilemap, min, max, region, &tiles, &mut commands)
+            })
+            .unwrap()
+    }
+
+    fn flood_fill(world: &mut World, tilemap: Entity, start: UVec2, region: AssetId<AtlasRegion>) -> Vec<TileEdit> {
+        world
+            .run_system_once(move |tilemaps: Query<&Tilemap>, tiles: Query<&Tile>, mut commands: Commands| {
+                tilemaps.get(tilemap).unwrap().flood_fill(tilemap, start, region, &tiles, &mut commands)
+            })
+            .unwrap()
+    }
+
+    fn undo(world: &mut World) -> bool {
+        world
+            .run_system_once(|mut history: ResMut<TileHistory>, tilemaps: Query<&Tilemap>, mut commands: Commands| {
+                history.undo(&tilemaps, &mut commands)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn fill_rect_clamps_to_bounds() {
+        let mut world = World::new();
+        let tilemap = spawn_tilemap(&mut world, uvec2(4, 3));
+
+        fill_rect(&mut world, tilemap, uvec2(1, 1), uvec2(10, 10), A);
+        assert_eq!(regions(&world, tilemap), [
+            vec![None, None, None, None],
+            vec![None, Some(A), Some(A), Some(A)],
+            vec![None, Some(A), Some(A), Some(A)],
+        ]);
+    }
+
+    #[test]
+    fn flood_fill_stops_at_region_borders() {
+        let mut world = World::new();
+        let tilemap = spawn_tilemap(&mut world, uvec2(5, 3));
+
+        // A wall of `A` splitting the empty map in two.
+        fill_rect(&mut world, tilemap, uvec2(2, 0), uvec2(2, 2), A);
+        flood_fill(&mut world, tilemap, uvec2(0, 1), B);
+        assert_eq!(regions(&world, tilemap), [vec![Some(B), Some(B), Some(A), None, None]; 3]);
+
+        // Replacing the wall only affects the wall itself.
+        flood_fill(&mut world, tilemap, uvec2(2, 0), B);
+        assert_eq!(regions(&world, tilemap), [vec![Some(B), Some(B), Some(B), None, None]; 3]);
+    }
+
+    #[test]
+    fn flood_fill_stops_at_map_bounds() {
+        let mut world = World::new();
+        let tilemap = spawn_tilemap(&mut world, uvec2(3, 3));
+
+        flood_fill(&mut world, tilemap, uvec2(3, 0), B);
+        assert_eq!(regions(&world, tilemap), [vec![None; 3]; 3]);
+
+        flood_fill(&mut world, tilemap, uvec2(2, 2), B);
+        assert_eq!(regions(&world, tilemap), [vec![Some(B); 3]; 3]);
+        assert_eq!(world.query::<&Tile>().iter(&world).count(), 9);
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut world = World::new();
+        world.init_resource::<TileHistory>();
+        let tilemap = spawn_tilemap(&mut world, uvec2(2, 1));
+
+        let flip = BVec2::new(true, false);
+        world.spawn(Tile::new(tilemap, uvec2(0, 0), A).with_flip(flip));
+        let flipped_a = TileLook { region: A, flip };
+
+        // Paint over the flipped tile, and paint the empty cell twice in one step.
+        world.spawn(Tile::new(tilemap, uvec2(0, 0), B));
+        world.spawn(Tile::new(tilemap, uvec2(1, 0), A));
+        world.spawn(Tile::new(tilemap, uvec2(1, 0), B));
+        world.resource_mut::<TileHistory>().record([
+            TileEdit {
+                tilemap,
+                pos: uvec2(0, 0),
+                old: Some(flipped_a),
+                new: Some(B.into()),
+            },
+            TileEdit {
+                tilemap,
+                pos: uvec2(1, 0),
+                old: None,
+                new: Some(A.into()),
+            },
+            TileEdit {
+                tilemap,
+                pos: uvec2(1, 0),
+                old: Some(A.into()),
+                new: Some(B.into()),
+            },
+        ]);
+
+        let looks = |world: &World| {
+            let tilemap = world.get::<Tilemap>(tilemap).unwrap();
+            [uvec2(0, 0), uvec2(1, 0)].map(|pos| tilemap.get(pos).map(|tile| TileLook::from(world.get::<Tile>(tile).unwrap())))
+        };
+
+        let step = |world: &mut World, undo: bool| {
+            world
+                .run_system_once(
+                    move |mut history: ResMut<TileHistory>, tilemaps: Query<&Tilemap>, mut commands: Commands| match undo {
+                        true => history.undo(&tilemaps, &mut commands),
+                        false => history.redo(&tilemaps, &mut commands),
+                    },
+                )
+                .unwrap()
+        };
+
+        assert_eq!(looks(&world), [Some(TileLook::from(B)); 2]);
+
+        assert!(step(&mut world, true));
+        assert_eq!(looks(&world), [Some(flipped_a), None]);
+        assert!(!step(&mut world, true));
+
+        assert!(step(&mut world, false));
+        assert_eq!(looks(&world), [Some(TileLook::from(B)); 2]);
+        assert!(!step(&mut world, false));
+    }
+
+    #[test]
+    fn fills_return_their_edits() {
+        let mut world = World::new();
+        world.init_resource::<TileHistory>();
+        let tilemap = spawn_tilemap(&mut world, uvec2(3, 1));
+
+        let edits = fill_rect(&mut world, tilemap, uvec2(0, 0), uvec2(1, 0), A);
+        assert_eq!(edits.len(), 2);
+        assert!(edits.iter().all(|edit| edit.old.is_none() && edit.new == Some(A.into())));
+        world.resource_mut::<TileHistory>().record(edits);
+
+        let edits = flood_fill(&mut world, tilemap, uvec2(0, 0), B);
+        assert_eq!(edits.len(), 2);
+        assert!(edits.iter().all(|edit| edit.old == Some(A.into()) && edit.new == Some(B.into())));
+        world.resource_mut::<TileHistory>().record(edits);
+        assert_eq!(regions(&world, tilemap), [vec![Some(B), Some(B), None]]);
+
+        assert!(undo(&mut world));
+        assert_eq!(regions(&world, tilemap), [vec![Some(A), Some(A), None]]);
+        assert!(undo(&mut world));
+        assert_eq!(regions(&world, tilemap), [vec![None; 3]]);
+    }
+
+    #[test]
+    fn cursor_to_tile() {
+        // A 101x61 window at 2x scale has a 50x30 canvas centered in it, with half a physical
+        // pixel left over on each side.
+        let scale = PixelScale(2);
+        let window_size = uvec2(101, 61);
+        assert_eq!(scale.window_to_canvas(vec2(0.25, 30.), window_size), None);
+        assert_eq!(scale.window_to_canvas(vec2(50.25, 30.75), window_size), Some(vec2(24.875, 15.125)));
+
+        // What `Camera::viewport_to_world_2d()` does for a 1:1 orthographic camera at (100, 50),
+        // with the canvas' y axis pointing down.
+        let camera_pos = vec2(100., 50.);
+        let to_world = |canvas_pos: Vec2| camera_pos + (canvas_pos - scale.canvas_size(window_size).as_vec2() / 2.) * vec2(1., -1.);
+
+        // The cursor lands just left of and below the camera's center, i.e. in the top right of the
+        // tilemap's first cell; ignoring the border would have put it in the next cell.
+        let tilemap = Tilemap::new(8., uvec2(4, 4));
+        let trns = GlobalTransform2d::from(Transform2d {
+            translation: vec3(92., 42., 0.),
+            ..default()
+        });
+
+        let canvas_pos = scale.window_to_canvas(vec2(50.25, 30.75), window_size).unwrap();
+        assert_eq!(tilemap.tile_at(&trns, to_world(canvas_pos)), Some(uvec2(0, 0)));
+        assert_eq!(tilemap.tile_at(&trns, to_world(vec2(50.25, 30.75) / 2.)), Some(uvec2(1, 0)));
+        assert_eq!(tilemap.tile_at(&trns, camera_pos - 100.), None);
+    }
+
+    /// Meshes a 2x2-chunk tilemap whose chunks mix regions from two pages, returning each chunk
+    /// mesh's chunk position, texture and vertex positions in spawn order.
+    fn mesh_chunks() -> Vec<(Vec2, AssetId<Image>, Vec<[f32; 3]>)> {
+        ComputeTaskPool::get_or_init(TaskPool::default);
+
+        let mut world = World::new();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<ColorMaterial>>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<Assets<AtlasRegion>>();
+        world.init_resource::<TilemapUvInset>();
+        world.insert_resource(DeterministicRender(true));
+
+        let [a, b] = [(); 2].map(|_| {
+            let texture = world.resource_mut::<Assets<Image>>().add(Image::default());
+            world
+                .resource_mut::<Assets<AtlasRegion>>()
+                .add(AtlasRegion {
+                    info: AtlasInfo {
+                        page: PageInfo {
+                            texture,
+                            texture_size: UVec2::splat(16),
+                        },
+                        rect: URect::new(0, 0, 8, 8),
+                    },
+                })
+                .id()
+        });
+
+        let tilemap = spawn_tilemap(&mut world, uvec2(80, 70));
+        for chunk in [uvec2(1, 1), uvec2(0, 1), uvec2(1, 0), uvec2(0, 0)] {
+            let origin = chunk * TILEMAP_CHUNK_SIZE;
+            for (offset, region) in [(uvec2(1, 0), b), (uvec2(0, 0), a), (uvec2(0, 1), b)] {
+                world.spawn(Tile::new(tilemap, origin + offset, region));
+            }
+        }
+
+        world.run_system_once(update_tilemap_chunks).unwrap();
+
+        let mut chunk_meshes = world
+            .query::<(Entity, &ChildOf, &Mesh2d, &MeshMaterial2d<ColorMaterial>)>()
+            .iter(&world)
+            .map(|(e, child_of, mesh, material)| {
+                let chunk = world.get::<Transform2d>(child_of.parent()).unwrap().translation.truncate();
+                let texture = world
+                    .resource::<Assets<ColorMaterial>>()
+                    .get(&material.0)
+                    .unwrap()
+                    .texture
+                    .as_ref()
+                    .unwrap()
+                    .id();
+                let positions = world
+                    .resource::<Assets<Mesh>>()
+                    .get(&mesh.0)
+                    .unwrap()
+                    .attribute(Mesh::ATTRIBUTE_POSITION)
+                    .unwrap();
+                (e, (chunk, texture, positions.as_float3().unwrap().to_vec()))
+            })
+            .collect::<Vec<_>>();
+        chunk_meshes.sort_by_key(|&(e, ..)| e);
+        chunk_meshes.into_iter().map(|(_, mesh)| mesh).collect()
+    }
+
+    #[test]
+    fn deterministic_chunk_order() {
+        let meshes = mesh_chunks();
+        for _ in 0..8 {
+            assert_eq!(mesh_chunks(), meshes);
+        }
+
+        // Chunks are meshed row by row from the bottom, and each chunk's meshes by texture.
+        let chunk = TILEMAP_CHUNK_SIZE as f32 * 8.;
+        assert_eq!(meshes.iter().map(|&(pos, ..)| pos).collect::<Vec<_>>(), [
+            vec2(0., 0.),
+            vec2(0., 0.),
+            vec2(chunk, 0.),
+            vec2(chunk, 0.),
+            vec2(0., chunk),
+            vec2(0., chunk),
+            vec2(chunk, chunk),
+            vec2(chunk, chunk),
+        ]);
+        assert!(meshes.chunks(2).all(|pair| pair[0].1 < pair[1].1));
+
+        // Quads within a mesh follow the cells' order, not the tiles' spawn order.
+        assert!(
+            meshes
+                .iter()
+                .all(|(.., positions)| positions.chunks(4).is_sorted_by_key(|quad| (quad[0][1] as u32, quad[0][0] as u32)))
+        );
+    }
+
+    #[test]
+    fn tile_animation_frames() {
+        let c = AssetId::Uuid { uuid: Uuid::from_u128(3) };
+        let anim = TileAnimation {
+            frames: vec![A, B, c],
+            fps: 4.,
+        };
+
+        let frames = [0., 0.2, 0.3, 0.6, 0.8, 1.1].map(|secs| anim.frame_at(Duration::from_secs_f32(secs)));
+        assert_eq!(frames, [Some(A), Some(A), Some(B), Some(c), Some(A), Some(B)]);
+
+        let still = TileAnimation { fps: 0., ..anim.clone() };
+        assert_eq!(still.frame_at(Duration::from_secs(10)), Some(A));
+        let empty = TileAnimation {
+            frames: vec![],
+            ..anim.clone()
+        };
+        assert_eq!(empty.frame_at(Duration::from_secs(10)), None);
+
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        let tilemap = spawn_tilemap(&mut world, uvec2(1, 1));
+        let tile = world.spawn((Tile::new(tilemap, UVec2::ZERO, A), anim)).id();
+
+        let step = |world: &mut World, secs: f32| {
+            world.resource_mut::<Time>().advance_by(Duration::from_secs_f32(secs));
+            world.run_system_once(update_tile_animations).unwrap();
+            world.get::<Tile>(tile).unwrap().region
+        };
+        assert_eq!(step(&mut world, 0.3), B);
+        assert_eq!(step(&mut world, 0.3), c);
+        // Wraps back around to the first frame.
+        assert_eq!(step(&mut world, 0.2), A);
+    }
+
+    #[test]
+    fn cull_far_chunks() {
+        let mut world = World::new();
+        world.init_resource::<PixelScale>();
+        // A 1280x720 window, i.e. a 320x180 canvas.
+        world.spawn((Window::default(), PrimaryWindow));
+        let camera = world.spawn((MainCamera::default(), GlobalTransform::from_xyz(160., 90., 0.))).id();
+
+        let tilemap = spawn_tilemap(&mut world, uvec2(200, 10));
+        let chunks = [
+            Affine2::from_translation(vec2(0., 0.)),
+            Affine2::from_translation(vec2(512., 0.)),
+            Affine2::from_translation(vec2(1024., 0.)),
+            // The same chunk as above, but pulled into view by parallax.
+            Affine2::from_scale_angle_translation(Vec2::splat(0.5), 0., vec2(112., 0.)),
+        ]
+        .map(|affine| {
+            world
+                .spawn((TilemapChunk { size: uvec2(64, 10) }, ChildOf(tilemap), GlobalTransform2d {
+                    affine,
+                    z: 0.,
+                }))
+                .id()
+        });
+
+        let cull = |world: &mut World| {
+            world.run_system_once(cull_tilemap_chunks).unwrap();
+            chunks.map(|e| *world.get::<Visibility>(e).unwrap())
+        };
+
+        use Visibility::{Hidden, Inherited};
+        assert_eq!(cull(&mut world), [Inherited, Hidden, Hidden, Inherited]);
+
+        *world.get_mut::<GlobalTransform>(camera).unwrap() = GlobalTransform::from_xyz(1100., 90., 0.);
+        assert_eq!(cull(&mut world), [Hidden, Inherited, Inherited, Hidden]);
+    }
+}